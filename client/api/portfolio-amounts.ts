@@ -4,15 +4,35 @@ import * as dec from "decoders";
 export type PortfolioAmount = {
   grossTotal: number;
   invested: number;
+  income: number;
+  cash: number;
+  contributions: number;
   date: number;
 };
 
 const portfolioAmountDecoder = dec.map(
-  dec.tuple3(dec.string, dec.number, dec.number),
-  ([dateString, invested, grossTotal]): PortfolioAmount => ({
+  dec.tuple6(
+    dec.string,
+    dec.number,
+    dec.number,
+    dec.number,
+    dec.number,
+    dec.number
+  ),
+  ([
+    dateString,
+    invested,
+    grossTotal,
+    income,
+    cash,
+    contributions,
+  ]): PortfolioAmount => ({
     date: Date.parse(dateString),
     grossTotal,
     invested,
+    income,
+    cash,
+    contributions,
   })
 );
 
@@ -3,6 +3,11 @@ import * as dec from "decoders";
 
 type Assetable =
   | { type: "Etf"; data: string }
+  | { type: "Stock"; data: string }
+  | { type: "Fii"; data: string }
+  | { type: "Fund"; data: string }
+  | { type: "Crypto"; data: string }
+  | { type: "PrivateBond"; data: string }
   | {
       type: "TreasuryBond";
       data: string;
@@ -21,7 +26,9 @@ export interface PortfolioPosition {
 }
 
 const assetableDecoder: dec.Decoder<Assetable> = dec.object({
-  type: dec.oneOf<"TreasuryBond" | "Etf">(["TreasuryBond", "Etf"]),
+  type: dec.oneOf<
+    "TreasuryBond" | "Etf" | "Stock" | "Fii" | "Fund" | "Crypto" | "PrivateBond"
+  >(["TreasuryBond", "Etf", "Stock", "Fii", "Fund", "Crypto", "PrivateBond"]),
   data: dec.string,
 });
 
@@ -3,7 +3,12 @@ import * as dec from "decoders";
 
 export type Assetable =
   | { type: "TreasuryBond"; data: string }
-  | { type: "Etf"; data: string };
+  | { type: "Etf"; data: string }
+  | { type: "Stock"; data: string }
+  | { type: "Fii"; data: string }
+  | { type: "Fund"; data: string }
+  | { type: "Crypto"; data: string }
+  | { type: "PrivateBond"; data: string };
 
 export interface Transaction {
   assetable: Assetable;
@@ -11,6 +16,7 @@ export interface Transaction {
   price: number;
   quantity: number;
   amount: number;
+  fees: number;
 }
 
 const dateDecoder: dec.Decoder<number> = dec.compose(
@@ -19,7 +25,9 @@ const dateDecoder: dec.Decoder<number> = dec.compose(
 );
 
 const assetableDecoder: dec.Decoder<Assetable> = dec.object({
-  type: dec.oneOf<"TreasuryBond" | "Etf">(["TreasuryBond", "Etf"]),
+  type: dec.oneOf<
+    "TreasuryBond" | "Etf" | "Stock" | "Fii" | "Fund" | "Crypto" | "PrivateBond"
+  >(["TreasuryBond", "Etf", "Stock", "Fii", "Fund", "Crypto", "PrivateBond"]),
   data: dec.string,
 });
 
@@ -29,6 +37,7 @@ const transactionDecoder: dec.Decoder<Transaction> = dec.object({
   price: dec.number,
   quantity: dec.number,
   amount: dec.number,
+  fees: dec.number,
 });
 
 const decode = dec.guard(dec.array(transactionDecoder));
@@ -27,6 +27,16 @@ const Position = ({ portfolioPortfolio }: PositionProps) => {
                     <td>
                       {asset.assetable.type === "TreasuryBond"
                         ? "Tesouro SELIC"
+                        : asset.assetable.type === "Stock"
+                        ? `Ação ${asset.assetable.data}`
+                        : asset.assetable.type === "Fii"
+                        ? `FII ${asset.assetable.data}`
+                        : asset.assetable.type === "Fund"
+                        ? `Fundo ${asset.assetable.data}`
+                        : asset.assetable.type === "Crypto"
+                        ? `Cripto ${asset.assetable.data}`
+                        : asset.assetable.type === "PrivateBond"
+                        ? `Privado ${asset.assetable.data}`
                         : `ETF ${asset.assetable.data}`}
                     </td>
                     <td>{asset.amount}</td>
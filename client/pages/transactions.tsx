@@ -30,6 +30,16 @@ const formatAssetable = (assetable: Assetable): string => {
   switch (assetable.type) {
     case "Etf":
       return `ETF ${assetable.data}`;
+    case "Stock":
+      return `Ação ${assetable.data}`;
+    case "Fii":
+      return `FII ${assetable.data}`;
+    case "Fund":
+      return `Fundo ${assetable.data}`;
+    case "Crypto":
+      return `Cripto ${assetable.data}`;
+    case "PrivateBond":
+      return `Privado ${assetable.data}`;
     case "TreasuryBond":
       return `LFT ${assetable.data}`;
   }
@@ -0,0 +1,7 @@
+CREATE TABLE benchmarks (
+	id SERIAL PRIMARY KEY,
+	key TEXT NOT NULL CHECK (key in ('CDI', 'Selic', 'IPCA', 'Ibovespa')),
+	date DATE NOT NULL,
+	value DECIMAL NOT NULL,
+	UNIQUE (key, date)
+);
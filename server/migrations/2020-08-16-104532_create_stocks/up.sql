@@ -0,0 +1,10 @@
+ALTER TABLE assets DROP CONSTRAINT assets_kind_check;
+ALTER TABLE assets ADD CONSTRAINT assets_kind_check CHECK (kind in ('treasury_bond', 'etf', 'stock'));
+
+CREATE TABLE stocks (
+	id int PRIMARY KEY,
+	kind TEXT NOT NULL DEFAULT 'stock' CHECK (kind = 'stock'),
+	ticker TEXT NOT NULL,
+	FOREIGN KEY (id, kind) REFERENCES assets (id, kind),
+	UNIQUE(ticker)
+);
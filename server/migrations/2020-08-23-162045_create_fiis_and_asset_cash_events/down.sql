@@ -0,0 +1,5 @@
+DROP TABLE asset_cash_events;
+DROP TABLE fiis;
+
+ALTER TABLE assets DROP CONSTRAINT assets_kind_check;
+ALTER TABLE assets ADD CONSTRAINT assets_kind_check CHECK (kind in ('treasury_bond', 'etf', 'stock'));
@@ -0,0 +1,20 @@
+ALTER TABLE assets DROP CONSTRAINT assets_kind_check;
+ALTER TABLE assets ADD CONSTRAINT assets_kind_check CHECK (kind in ('treasury_bond', 'etf', 'stock', 'fii'));
+
+CREATE TABLE fiis (
+	id int PRIMARY KEY,
+	kind TEXT NOT NULL DEFAULT 'fii' CHECK (kind = 'fii'),
+	ticker TEXT NOT NULL,
+	FOREIGN KEY (id, kind) REFERENCES assets (id, kind),
+	UNIQUE(ticker)
+);
+
+CREATE TABLE asset_cash_events (
+	id SERIAL PRIMARY KEY,
+	asset_id INTEGER NOT NULL REFERENCES assets,
+	kind TEXT NOT NULL CHECK (kind in ('distribution')),
+	date DATE NOT NULL,
+	payment_date DATE NOT NULL CHECK (payment_date >= date),
+	value DECIMAL NOT NULL CHECK (value > 0),
+	UNIQUE (asset_id, kind, date)
+);
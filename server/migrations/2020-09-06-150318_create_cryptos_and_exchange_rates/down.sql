@@ -0,0 +1,5 @@
+DROP TABLE exchange_rates;
+DROP TABLE cryptos;
+
+ALTER TABLE assets DROP CONSTRAINT assets_kind_check;
+ALTER TABLE assets ADD CONSTRAINT assets_kind_check CHECK (kind in ('treasury_bond', 'etf', 'stock', 'fii', 'fund'));
@@ -0,0 +1,19 @@
+ALTER TABLE assets DROP CONSTRAINT assets_kind_check;
+ALTER TABLE assets ADD CONSTRAINT assets_kind_check CHECK (kind in ('treasury_bond', 'etf', 'stock', 'fii', 'fund', 'crypto'));
+
+CREATE TABLE cryptos (
+	id int PRIMARY KEY,
+	kind TEXT NOT NULL DEFAULT 'crypto' CHECK (kind = 'crypto'),
+	symbol TEXT NOT NULL,
+	currency TEXT NOT NULL CHECK (currency in ('BRL', 'USD')),
+	FOREIGN KEY (id, kind) REFERENCES assets (id, kind),
+	UNIQUE(symbol)
+);
+
+CREATE TABLE exchange_rates (
+	id SERIAL PRIMARY KEY,
+	currency TEXT NOT NULL CHECK (currency in ('USD')),
+	date DATE NOT NULL,
+	rate DECIMAL NOT NULL CHECK (rate > 0),
+	UNIQUE(currency, date)
+);
@@ -0,0 +1,4 @@
+DROP TABLE private_bonds;
+
+ALTER TABLE assets DROP CONSTRAINT assets_kind_check;
+ALTER TABLE assets ADD CONSTRAINT assets_kind_check CHECK (kind in ('treasury_bond', 'etf', 'stock', 'fii', 'fund', 'crypto'));
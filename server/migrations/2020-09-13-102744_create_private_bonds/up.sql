@@ -0,0 +1,17 @@
+ALTER TABLE assets DROP CONSTRAINT assets_kind_check;
+ALTER TABLE assets ADD CONSTRAINT assets_kind_check CHECK (kind in ('treasury_bond', 'etf', 'stock', 'fii', 'fund', 'crypto', 'private_bond'));
+
+CREATE TABLE private_bonds (
+	id int PRIMARY KEY,
+	kind TEXT NOT NULL DEFAULT 'private_bond' CHECK (kind = 'private_bond'),
+	code TEXT NOT NULL,
+	issuer TEXT NOT NULL,
+	bond_kind TEXT NOT NULL CHECK (bond_kind in ('CDB', 'LCI', 'LCA', 'Debenture')),
+	index TEXT NOT NULL CHECK (index in ('Pre', 'CDI', 'IPCA')),
+	rate DECIMAL NOT NULL CHECK (rate >= 0),
+	issue_date DATE NOT NULL,
+	maturity_date DATE NOT NULL CHECK (maturity_date > issue_date),
+	issue_price DECIMAL NOT NULL CHECK (issue_price > 0),
+	FOREIGN KEY (id, kind) REFERENCES assets (id, kind),
+	UNIQUE(code)
+);
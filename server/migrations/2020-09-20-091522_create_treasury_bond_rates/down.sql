@@ -0,0 +1 @@
+DROP TABLE treasury_bond_rates;
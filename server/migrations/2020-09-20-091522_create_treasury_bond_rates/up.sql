@@ -0,0 +1,8 @@
+CREATE TABLE treasury_bond_rates (
+	id SERIAL PRIMARY KEY,
+	asset_id int NOT NULL REFERENCES treasury_bonds (id),
+	date DATE NOT NULL,
+	buy_rate DECIMAL,
+	sell_rate DECIMAL NOT NULL,
+	UNIQUE(asset_id, date)
+);
@@ -0,0 +1,14 @@
+ALTER TABLE asset_cash_events DROP CONSTRAINT asset_cash_events_kind_check;
+ALTER TABLE asset_cash_events ADD CONSTRAINT asset_cash_events_kind_check CHECK (kind in ('distribution', 'coupon'));
+
+ALTER TABLE benchmarks DROP CONSTRAINT benchmarks_key_check;
+ALTER TABLE benchmarks ADD CONSTRAINT benchmarks_key_check CHECK (key in ('CDI', 'Selic', 'IPCA', 'IGPM', 'Ibovespa'));
+
+-- Tesouro IPCA+ (NTN-B Principal) and Tesouro IPCA+ com Juros Semestrais (NTN-B) were imported swapped
+ALTER TABLE treasury_bonds DROP CONSTRAINT treasury_bonds_key_check;
+UPDATE treasury_bonds SET key = 'NTN-B Swap' WHERE key = 'NTN-B';
+UPDATE treasury_bonds SET key = 'NTN-B' WHERE key = 'NTN-B Principal';
+UPDATE treasury_bonds SET key = 'NTN-B Principal' WHERE key = 'NTN-B Swap';
+ALTER TABLE treasury_bonds
+ADD CONSTRAINT treasury_bonds_key_check
+CHECK (key in ('LTN', 'NTN-F', 'LFT', 'NTN-B', 'NTN-B Principal', 'NTN-C'));
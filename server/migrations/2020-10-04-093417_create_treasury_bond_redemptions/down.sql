@@ -0,0 +1,3 @@
+DELETE FROM asset_cash_events WHERE kind = 'redemption';
+ALTER TABLE asset_cash_events DROP CONSTRAINT asset_cash_events_kind_check;
+ALTER TABLE asset_cash_events ADD CONSTRAINT asset_cash_events_kind_check CHECK (kind in ('distribution', 'coupon'));
@@ -0,0 +1,2 @@
+ALTER TABLE asset_cash_events DROP CONSTRAINT asset_cash_events_kind_check;
+ALTER TABLE asset_cash_events ADD CONSTRAINT asset_cash_events_kind_check CHECK (kind in ('distribution', 'coupon', 'redemption'));
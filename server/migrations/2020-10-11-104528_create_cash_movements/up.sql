@@ -0,0 +1,7 @@
+CREATE TABLE cash_movements (
+	id SERIAL PRIMARY KEY,
+	portfolio_id int NOT NULL REFERENCES portfolios (id),
+	date DATE NOT NULL,
+	kind TEXT NOT NULL CHECK (kind in ('deposit', 'withdrawal', 'fee')),
+	amount DECIMAL NOT NULL CHECK (amount > 0)
+);
@@ -0,0 +1 @@
+ALTER TABLE trades DROP COLUMN brokerage_fee, DROP COLUMN emoluments;
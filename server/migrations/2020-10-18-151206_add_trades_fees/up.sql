@@ -0,0 +1,3 @@
+ALTER TABLE trades
+ADD COLUMN brokerage_fee DECIMAL NOT NULL DEFAULT 0 CHECK (brokerage_fee >= 0),
+ADD COLUMN emoluments DECIMAL NOT NULL DEFAULT 0 CHECK (emoluments >= 0);
@@ -0,0 +1,10 @@
+CREATE TABLE corporate_actions (
+	id SERIAL PRIMARY KEY,
+	asset_id int NOT NULL REFERENCES assets (id),
+	kind TEXT NOT NULL CHECK (kind in ('split', 'reverse_split', 'rename', 'merger')),
+	date DATE NOT NULL,
+	ratio DECIMAL NOT NULL CHECK (ratio > 0),
+	new_asset_id int REFERENCES assets (id),
+	CHECK ((kind in ('rename', 'merger')) = (new_asset_id IS NOT NULL)),
+	UNIQUE(asset_id, date, kind)
+);
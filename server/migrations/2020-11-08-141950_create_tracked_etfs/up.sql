@@ -0,0 +1,11 @@
+CREATE TABLE tracked_etfs (
+	id SERIAL PRIMARY KEY,
+	ticker TEXT NOT NULL UNIQUE,
+	curr_id TEXT NOT NULL,
+	sml_id TEXT NOT NULL
+);
+
+INSERT INTO tracked_etfs (ticker, curr_id, sml_id) VALUES
+	('BOVA11', '39004', '2514218'),
+	('SMAL11', '39013', '2514317'),
+	('IVVB11', '956435', '2585374');
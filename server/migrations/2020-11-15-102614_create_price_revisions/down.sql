@@ -0,0 +1,2 @@
+DROP TABLE price_revisions;
+DROP TABLE import_runs;
@@ -0,0 +1,19 @@
+CREATE TABLE import_runs (
+	id SERIAL PRIMARY KEY,
+	source TEXT NOT NULL,
+	started_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE price_revisions (
+	id SERIAL PRIMARY KEY,
+	asset_id INTEGER NOT NULL REFERENCES assets(id),
+	date DATE NOT NULL,
+	price DECIMAL NOT NULL,
+	import_run_id INTEGER REFERENCES import_runs(id),
+	created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX price_revisions_asset_id_date_idx ON price_revisions (asset_id, date);
+
+INSERT INTO price_revisions (asset_id, date, price)
+	SELECT asset_id, date, price FROM asset_prices;
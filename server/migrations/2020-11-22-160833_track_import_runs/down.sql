@@ -0,0 +1,2 @@
+DROP TABLE import_run_assets;
+ALTER TABLE import_runs DROP COLUMN finished_at, DROP COLUMN outcome, DROP COLUMN error;
@@ -0,0 +1,14 @@
+ALTER TABLE import_runs
+ADD COLUMN finished_at TIMESTAMP,
+ADD COLUMN outcome TEXT CHECK (outcome IN ('succeeded', 'partial', 'failed')),
+ADD COLUMN error TEXT;
+
+CREATE TABLE import_run_assets (
+	id SERIAL PRIMARY KEY,
+	import_run_id INTEGER NOT NULL REFERENCES import_runs(id),
+	asset TEXT NOT NULL,
+	inserted INTEGER NOT NULL DEFAULT 0,
+	updated INTEGER NOT NULL DEFAULT 0,
+	unchanged INTEGER NOT NULL DEFAULT 0,
+	error TEXT
+);
@@ -1,9 +1,58 @@
 mod assets;
+mod benchmarks;
+mod cash;
+mod cash_events;
+mod corporate_actions;
+mod cost_bases;
+mod custody_fees;
+mod exchange_rates;
+mod import_runs;
 mod positions;
+mod price_revisions;
 mod prices;
+mod private_bonds;
+mod taxes;
+mod tracked_etfs;
 mod trades;
+mod treasury_bond_rates;
 
-pub use assets::{retrieve_assetables, Assetable};
+pub use assets::{
+    crypto_currency, known_asset_id, retrieve_assetables, ticker_asset_id, treasury_bond_asset_id,
+    Assetable,
+};
+pub use benchmarks::{benchmark_indexes, register_benchmark_values, Benchmark};
+pub use cash::{
+    cash_balance, cash_ledger, net_contributions, register_cash_movements, CashKind, CashMovement,
+};
+pub use cash_events::{
+    incomes, redemptions, register_income_events, register_treasury_bond_coupons,
+    register_treasury_bond_redemption, IncomeEvent, IncomeKind,
+};
+pub use corporate_actions::{
+    adjustment, corporate_actions, register_corporate_actions, successor_asset_ids,
+    CorporateAction, CorporateActionKind,
+};
+pub use cost_bases::Sell;
+pub use custody_fees::custody_fees;
+pub use exchange_rates::{brl_prices, register_exchange_rates, Currency};
+pub use import_runs::{
+    finish_assetless_import_run, finish_import_run, import_runs, register_import_run,
+    ImportOutcome, ImportRun, ImportRunAsset,
+};
 pub use positions::{position, AssetPosition, PortfolioPosition};
-pub use prices::{register_etf_prices, register_treasury_bond_prices};
-pub use trades::{register_trades, EtfTrade, TreasuryBondTrade};
+pub use price_revisions::{price_revisions, PriceRevision};
+pub use prices::{
+    last_etf_price_date, last_treasury_bond_price_date, register_crypto_prices,
+    register_etf_prices, register_fii_prices, register_fund_prices, register_stock_prices,
+    register_treasury_bond_prices, PriceCounts,
+};
+pub use private_bonds::{
+    private_bond_prices, register_private_bonds, BondIndex, BondKind, PrivateBondIssue,
+};
+pub use taxes::{monthly_taxes, MonthlyTax};
+pub use tracked_etfs::{register_tracked_etf, remove_tracked_etf, tracked_etfs, TrackedEtf};
+pub use trades::{
+    register_crypto_trades, register_trades, CryptoTrade, EtfTrade, FiiTrade, FundTrade,
+    PortfolioTrades, PrivateBondTrade, StockTrade, TradeFees, TreasuryBondTrade,
+};
+pub use treasury_bond_rates::register_treasury_bond_rates;
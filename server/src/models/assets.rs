@@ -1,4 +1,7 @@
-use crate::schema::{assets, etfs, treasury_bonds};
+use crate::models::exchange_rates::Currency;
+use crate::models::private_bonds::{BondIndex, BondKind, PrivateBondIssue};
+use crate::schema::{assets, cryptos, etfs, fiis, funds, private_bonds, stocks, treasury_bonds};
+use bigdecimal::BigDecimal;
 use chrono::NaiveDate;
 use diesel::pg::PgConnection;
 use diesel::prelude::*;
@@ -27,6 +30,72 @@ pub struct Etf {
     pub ticker: String,
 }
 
+#[allow(dead_code)]
+#[derive(Queryable, Clone)]
+pub struct Stock {
+    pub id: i32,
+    kind: String,
+    pub ticker: String,
+}
+
+#[allow(dead_code)]
+#[derive(Queryable, Clone)]
+pub struct Fii {
+    pub id: i32,
+    kind: String,
+    pub ticker: String,
+}
+
+#[allow(dead_code)]
+#[derive(Queryable, Clone)]
+pub struct Fund {
+    pub id: i32,
+    kind: String,
+    pub cnpj: String,
+}
+
+#[allow(dead_code)]
+#[derive(Queryable, Clone)]
+pub struct Crypto {
+    pub id: i32,
+    kind: String,
+    pub symbol: String,
+    pub currency: String,
+}
+
+#[allow(dead_code)]
+#[derive(Queryable, Clone)]
+pub struct PrivateBond {
+    pub id: i32,
+    kind: String,
+    pub code: String,
+    pub issuer: String,
+    bond_kind: String,
+    index: String,
+    pub rate: BigDecimal,
+    pub issue_date: NaiveDate,
+    pub maturity_date: NaiveDate,
+    pub issue_price: BigDecimal,
+}
+
+impl PrivateBond {
+    pub fn bond_kind(&self) -> BondKind {
+        BondKind::from_key(&self.bond_kind).unwrap()
+    }
+
+    pub fn index(&self) -> BondIndex {
+        BondIndex::from_key(&self.index).unwrap()
+    }
+
+    // LCI and LCA income is exempt from income tax for individuals
+    pub fn is_tax_exempt(&self) -> bool {
+        match self.bond_kind() {
+            BondKind::Lci | BondKind::Lca => true,
+            BondKind::Cdb | BondKind::Debenture => false,
+        }
+    }
+}
+
 #[derive(Insertable)]
 #[table_name = "assets"]
 struct NewAsset {
@@ -48,10 +117,72 @@ struct NewEtf<'a> {
     ticker: &'a str,
 }
 
+#[derive(Insertable)]
+#[table_name = "stocks"]
+struct NewStock<'a> {
+    id: i32,
+    ticker: &'a str,
+}
+
+#[derive(Insertable)]
+#[table_name = "fiis"]
+struct NewFii<'a> {
+    id: i32,
+    ticker: &'a str,
+}
+
+#[derive(Insertable)]
+#[table_name = "funds"]
+struct NewFund<'a> {
+    id: i32,
+    cnpj: &'a str,
+}
+
+#[derive(Insertable)]
+#[table_name = "cryptos"]
+struct NewCrypto<'a> {
+    id: i32,
+    symbol: &'a str,
+    currency: &'static str,
+}
+
+#[derive(Insertable)]
+#[table_name = "private_bonds"]
+struct NewPrivateBond<'a> {
+    id: i32,
+    code: &'a str,
+    issuer: &'a str,
+    bond_kind: &'static str,
+    index: &'static str,
+    rate: &'a BigDecimal,
+    issue_date: &'a NaiveDate,
+    maturity_date: &'a NaiveDate,
+    issue_price: &'a BigDecimal,
+}
+
 #[derive(Clone)]
 pub enum Assetable {
     TreasuryBond(TreasuryBond),
     Etf(Etf),
+    Stock(Stock),
+    Fii(Fii),
+    Fund(Fund),
+    Crypto(Crypto),
+    PrivateBond(PrivateBond),
+}
+
+impl Assetable {
+    pub fn id(&self) -> i32 {
+        match self {
+            Assetable::TreasuryBond(treasury_bond) => treasury_bond.id,
+            Assetable::Etf(etf) => etf.id,
+            Assetable::Stock(stock) => stock.id,
+            Assetable::Fii(fii) => fii.id,
+            Assetable::Fund(fund) => fund.id,
+            Assetable::Crypto(crypto) => crypto.id,
+            Assetable::PrivateBond(private_bond) => private_bond.id,
+        }
+    }
 }
 
 pub fn register_treasury_bond_asset(
@@ -61,6 +192,7 @@ pub fn register_treasury_bond_asset(
 ) -> QueryResult<i32> {
     let treasury_bond = treasury_bonds::table
         .filter(treasury_bonds::maturity_date.eq(maturity_date))
+        .filter(treasury_bonds::key.eq(key))
         .first::<TreasuryBond>(conn)
         .optional()?;
 
@@ -109,11 +241,176 @@ pub fn register_etf_asset(conn: &PgConnection, ticker: &str) -> QueryResult<i32>
     Ok(etf.id)
 }
 
+pub fn register_stock_asset(conn: &PgConnection, ticker: &str) -> QueryResult<i32> {
+    let stock = stocks::table
+        .filter(stocks::ticker.eq(&ticker))
+        .first::<Stock>(conn)
+        .optional()?;
+
+    if let Some(stock) = stock {
+        return Ok(stock.id);
+    }
+
+    let asset = diesel::insert_into(assets::table)
+        .values(&NewAsset { kind: "stock" })
+        .get_result::<Asset>(conn)?;
+
+    let stock = diesel::insert_into(stocks::table)
+        .values(&NewStock {
+            id: asset.id,
+            ticker,
+        })
+        .get_result::<Stock>(conn)?;
+
+    Ok(stock.id)
+}
+
+pub fn register_fii_asset(conn: &PgConnection, ticker: &str) -> QueryResult<i32> {
+    let fii = fiis::table
+        .filter(fiis::ticker.eq(&ticker))
+        .first::<Fii>(conn)
+        .optional()?;
+
+    if let Some(fii) = fii {
+        return Ok(fii.id);
+    }
+
+    let asset = diesel::insert_into(assets::table)
+        .values(&NewAsset { kind: "fii" })
+        .get_result::<Asset>(conn)?;
+
+    let fii = diesel::insert_into(fiis::table)
+        .values(&NewFii {
+            id: asset.id,
+            ticker,
+        })
+        .get_result::<Fii>(conn)?;
+
+    Ok(fii.id)
+}
+
+pub fn register_fund_asset(conn: &PgConnection, cnpj: &str) -> QueryResult<i32> {
+    let fund = funds::table
+        .filter(funds::cnpj.eq(&cnpj))
+        .first::<Fund>(conn)
+        .optional()?;
+
+    if let Some(fund) = fund {
+        return Ok(fund.id);
+    }
+
+    let asset = diesel::insert_into(assets::table)
+        .values(&NewAsset { kind: "fund" })
+        .get_result::<Asset>(conn)?;
+
+    let fund = diesel::insert_into(funds::table)
+        .values(&NewFund { id: asset.id, cnpj })
+        .get_result::<Fund>(conn)?;
+
+    Ok(fund.id)
+}
+
+// The currency is only set when the crypto is first seen, as its prices are kept in it
+pub fn register_crypto_asset(
+    conn: &PgConnection,
+    symbol: &str,
+    currency: Currency,
+) -> QueryResult<i32> {
+    let crypto = cryptos::table
+        .filter(cryptos::symbol.eq(&symbol))
+        .first::<Crypto>(conn)
+        .optional()?;
+
+    if let Some(crypto) = crypto {
+        return Ok(crypto.id);
+    }
+
+    let asset = diesel::insert_into(assets::table)
+        .values(&NewAsset { kind: "crypto" })
+        .get_result::<Asset>(conn)?;
+
+    let crypto = diesel::insert_into(cryptos::table)
+        .values(&NewCrypto {
+            id: asset.id,
+            currency: currency.key(),
+            symbol,
+        })
+        .get_result::<Crypto>(conn)?;
+
+    Ok(crypto.id)
+}
+
+// The currency is set when the crypto is first registered and its prices must keep to it
+pub fn crypto_currency(conn: &PgConnection, symbol: &str) -> QueryResult<Option<Currency>> {
+    Ok(cryptos::table
+        .select(cryptos::currency)
+        .filter(cryptos::symbol.eq(symbol))
+        .first::<String>(conn)
+        .optional()?
+        .and_then(|currency| Currency::from_key(&currency)))
+}
+
+pub fn register_private_bond_asset(
+    conn: &PgConnection,
+    issue: &PrivateBondIssue,
+) -> QueryResult<i32> {
+    let private_bond = private_bonds::table
+        .filter(private_bonds::code.eq(&issue.code))
+        .first::<PrivateBond>(conn)
+        .optional()?;
+
+    if let Some(private_bond) = private_bond {
+        return Ok(private_bond.id);
+    }
+
+    let asset = diesel::insert_into(assets::table)
+        .values(&NewAsset {
+            kind: "private_bond",
+        })
+        .get_result::<Asset>(conn)?;
+
+    let private_bond = diesel::insert_into(private_bonds::table)
+        .values(&NewPrivateBond {
+            id: asset.id,
+            code: &issue.code,
+            issuer: &issue.issuer,
+            bond_kind: issue.bond_kind.key(),
+            index: issue.index.key(),
+            rate: &issue.rate,
+            issue_date: &issue.issue_date,
+            maturity_date: &issue.maturity_date,
+            issue_price: &issue.issue_price,
+        })
+        .get_result::<PrivateBond>(conn)?;
+
+    Ok(private_bond.id)
+}
+
 pub fn retrieve_assetables(conn: &PgConnection, asset_ids: &[i32]) -> QueryResult<Vec<Assetable>> {
     let etfs = etfs::table
         .filter(etfs::id.eq_any(asset_ids))
         .load::<Etf>(conn)?;
 
+    let stocks = stocks::table
+        .filter(stocks::id.eq_any(asset_ids))
+        .load::<Stock>(conn)?;
+
+    let fiis = fiis::table
+        .filter(fiis::id.eq_any(asset_ids))
+        .load::<Fii>(conn)?;
+
+    let funds = funds::table
+        .filter(funds::id.eq_any(asset_ids))
+        .load::<Fund>(conn)?;
+
+    let cryptos = cryptos::table
+        .filter(cryptos::id.eq_any(asset_ids))
+        .load::<Crypto>(conn)?;
+
+    let private_bonds = private_bonds::table
+        .filter(private_bonds::id.eq_any(asset_ids))
+        .load::<PrivateBond>(conn)?;
+
     let treasury_bonds = treasury_bonds::table
         .filter(treasury_bonds::id.eq_any(asset_ids))
         .load::<TreasuryBond>(conn)?;
@@ -129,6 +426,51 @@ pub fn retrieve_assetables(conn: &PgConnection, asset_ids: &[i32]) -> QueryResul
                         None
                     }
                 })
+                .or_else(|| {
+                    stocks.iter().find_map(|stock| {
+                        if stock.id == *asset_id {
+                            Some(Assetable::Stock(stock.clone()))
+                        } else {
+                            None
+                        }
+                    })
+                })
+                .or_else(|| {
+                    fiis.iter().find_map(|fii| {
+                        if fii.id == *asset_id {
+                            Some(Assetable::Fii(fii.clone()))
+                        } else {
+                            None
+                        }
+                    })
+                })
+                .or_else(|| {
+                    funds.iter().find_map(|fund| {
+                        if fund.id == *asset_id {
+                            Some(Assetable::Fund(fund.clone()))
+                        } else {
+                            None
+                        }
+                    })
+                })
+                .or_else(|| {
+                    cryptos.iter().find_map(|crypto| {
+                        if crypto.id == *asset_id {
+                            Some(Assetable::Crypto(crypto.clone()))
+                        } else {
+                            None
+                        }
+                    })
+                })
+                .or_else(|| {
+                    private_bonds.iter().find_map(|private_bond| {
+                        if private_bond.id == *asset_id {
+                            Some(Assetable::PrivateBond(private_bond.clone()))
+                        } else {
+                            None
+                        }
+                    })
+                })
                 .or_else(|| {
                     treasury_bonds.iter().find_map(|treasury_bond| {
                         if treasury_bond.id == *asset_id {
@@ -142,3 +484,48 @@ pub fn retrieve_assetables(conn: &PgConnection, asset_ids: &[i32]) -> QueryResul
         })
         .collect())
 }
+
+// Any asset can be looked up by its id, to make sure it exists
+pub fn known_asset_id(conn: &PgConnection, asset_id: i32) -> QueryResult<i32> {
+    assets::table.select(assets::id).find(asset_id).first(conn)
+}
+
+// Tickers are unique across exchange listed assets, so any of them can be looked up by it
+pub fn ticker_asset_id(conn: &PgConnection, ticker: &str) -> QueryResult<i32> {
+    etfs::table
+        .select(etfs::id)
+        .filter(etfs::ticker.eq(ticker))
+        .first::<i32>(conn)
+        .optional()?
+        .map_or_else(
+            || {
+                stocks::table
+                    .select(stocks::id)
+                    .filter(stocks::ticker.eq(ticker))
+                    .first::<i32>(conn)
+                    .optional()
+            },
+            |id| Ok(Some(id)),
+        )?
+        .map_or_else(
+            || {
+                fiis::table
+                    .select(fiis::id)
+                    .filter(fiis::ticker.eq(ticker))
+                    .first::<i32>(conn)
+            },
+            Ok,
+        )
+}
+
+pub fn treasury_bond_asset_id(
+    conn: &PgConnection,
+    key: &str,
+    maturity_date: NaiveDate,
+) -> QueryResult<i32> {
+    treasury_bonds::table
+        .select(treasury_bonds::id)
+        .filter(treasury_bonds::key.eq(key))
+        .filter(treasury_bonds::maturity_date.eq(maturity_date))
+        .first(conn)
+}
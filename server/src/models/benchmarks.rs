@@ -0,0 +1,164 @@
+use crate::schema::benchmarks;
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::{Datelike, NaiveDate};
+use diesel::pg::upsert::excluded;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+
+#[derive(Clone, Copy)]
+pub enum Benchmark {
+    Cdi,
+    Selic,
+    Ipca,
+    Igpm,
+    Ibovespa,
+}
+
+impl Benchmark {
+    pub fn from_key(key: &str) -> Option<Benchmark> {
+        match key {
+            "CDI" => Some(Benchmark::Cdi),
+            "Selic" => Some(Benchmark::Selic),
+            "IPCA" => Some(Benchmark::Ipca),
+            "IGPM" => Some(Benchmark::Igpm),
+            "Ibovespa" => Some(Benchmark::Ibovespa),
+            _ => None,
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Benchmark::Cdi => "CDI",
+            Benchmark::Selic => "Selic",
+            Benchmark::Ipca => "IPCA",
+            Benchmark::Igpm => "IGPM",
+            Benchmark::Ibovespa => "Ibovespa",
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[table_name = "benchmarks"]
+struct NewBenchmarkValue<'a> {
+    key: &'static str,
+    date: &'a NaiveDate,
+    value: &'a BigDecimal,
+}
+
+pub fn register_benchmark_values(
+    conn: &PgConnection,
+    benchmark: Benchmark,
+    values: Vec<(NaiveDate, BigDecimal)>,
+) -> QueryResult<usize> {
+    let insertable_values: Vec<_> = values
+        .iter()
+        .map(|(date, value)| NewBenchmarkValue {
+            key: benchmark.key(),
+            date,
+            value,
+        })
+        .collect();
+
+    diesel::insert_into(benchmarks::table)
+        .values(&insertable_values)
+        .on_conflict((benchmarks::key, benchmarks::date))
+        .do_update()
+        .set(benchmarks::value.eq(excluded(benchmarks::value)))
+        .execute(conn)
+}
+
+pub fn benchmark_indexes(
+    conn: &PgConnection,
+    benchmark: Benchmark,
+    dates: &[NaiveDate],
+) -> QueryResult<Vec<Option<f64>>> {
+    let last_date = match dates.iter().max() {
+        Some(last_date) => *last_date,
+        None => return Ok(vec![]),
+    };
+
+    let values = benchmark_values(conn, benchmark, last_date)?;
+
+    Ok(dates
+        .iter()
+        .map(|date| match benchmark {
+            Benchmark::Cdi | Benchmark::Selic => daily_rate_index(&values, *date),
+            Benchmark::Ipca | Benchmark::Igpm => Some(monthly_rate_index(&values, *date)),
+            Benchmark::Ibovespa => points_index(&values, *date),
+        })
+        .collect())
+}
+
+pub(super) fn benchmark_values(
+    conn: &PgConnection,
+    benchmark: Benchmark,
+    last_date: NaiveDate,
+) -> QueryResult<Vec<(NaiveDate, f64)>> {
+    Ok(benchmarks::table
+        .select((benchmarks::date, benchmarks::value))
+        .filter(benchmarks::key.eq(benchmark.key()))
+        .filter(benchmarks::date.le(last_date))
+        .order(benchmarks::date)
+        .load::<(NaiveDate, BigDecimal)>(conn)?
+        .into_iter()
+        .map(|(date, value)| (date, value.to_f64().unwrap_or(0.0)))
+        .collect())
+}
+
+// CDI and Selic are percentages per business day, earned until the next one.
+// There's no index before the first rate, rather than one that never moved.
+fn daily_rate_index(rates: &[(NaiveDate, f64)], date: NaiveDate) -> Option<f64> {
+    match rates.first() {
+        Some((first_date, _)) if *first_date < date => Some(
+            rates
+                .iter()
+                .take_while(|(rate_date, _)| *rate_date < date)
+                .map(|(_, rate)| 1.0 + rate / 100.0)
+                .product(),
+        ),
+        _ => None,
+    }
+}
+
+// IPCA and IGPM are percentages per month, dated on their first day and spread evenly over it
+pub(super) fn monthly_rate_index(rates: &[(NaiveDate, f64)], date: NaiveDate) -> f64 {
+    rates
+        .iter()
+        .take_while(|(month, _)| *month <= date)
+        .map(|(month, rate)| {
+            let factor = 1.0 + rate / 100.0;
+
+            if month.year() == date.year() && month.month() == date.month() {
+                factor.powf(f64::from(date.day() - 1) / days_in_month(*month) as f64)
+            } else {
+                factor
+            }
+        })
+        .product()
+}
+
+// A monthly index only means something from the first month it has a rate for
+pub(super) fn monthly_rate_covers(rates: &[(NaiveDate, f64)], date: NaiveDate) -> bool {
+    match rates.first() {
+        Some((month, _)) => *month <= date,
+        None => false,
+    }
+}
+
+fn points_index(points: &[(NaiveDate, f64)], date: NaiveDate) -> Option<f64> {
+    points
+        .iter()
+        .take_while(|(points_date, _)| *points_date <= date)
+        .last()
+        .map(|(_, points)| *points)
+}
+
+fn days_in_month(month: NaiveDate) -> i64 {
+    let next_month = if month.month() == 12 {
+        NaiveDate::from_ymd(month.year() + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd(month.year(), month.month() + 1, 1)
+    };
+
+    (next_month - NaiveDate::from_ymd(month.year(), month.month(), 1)).num_days()
+}
@@ -0,0 +1,168 @@
+use crate::models::cash_events::incomes;
+use crate::models::custody_fees::custody_fees;
+use crate::schema::{cash_movements, trades};
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+
+#[derive(Insertable)]
+#[table_name = "cash_movements"]
+struct NewCashMovement<'a> {
+    portfolio_id: i32,
+    date: &'a NaiveDate,
+    kind: &'static str,
+    amount: &'a BigDecimal,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum CashKind {
+    Deposit,
+    Withdrawal,
+    Trade,
+    Income,
+    Fee,
+}
+
+impl CashKind {
+    fn from_key(key: &str) -> Option<CashKind> {
+        match key {
+            "deposit" => Some(CashKind::Deposit),
+            "withdrawal" => Some(CashKind::Withdrawal),
+            "fee" => Some(CashKind::Fee),
+            _ => None,
+        }
+    }
+
+    fn key(self) -> Option<&'static str> {
+        match self {
+            CashKind::Deposit => Some("deposit"),
+            CashKind::Withdrawal => Some("withdrawal"),
+            CashKind::Fee => Some("fee"),
+            CashKind::Trade | CashKind::Income => None,
+        }
+    }
+}
+
+// Amounts are always positive, the kind says which way the money went
+pub struct CashMovement {
+    pub date: NaiveDate,
+    pub kind: CashKind,
+    pub amount: BigDecimal,
+}
+
+// Amounts are signed, positive when money comes into the portfolio
+pub struct CashEntry {
+    pub date: NaiveDate,
+    pub kind: CashKind,
+    pub amount: BigDecimal,
+}
+
+pub fn register_cash_movements(
+    conn: &PgConnection,
+    portfolio_id: i32,
+    movements: &[CashMovement],
+) -> QueryResult<usize> {
+    let insertable_movements = movements
+        .iter()
+        .filter_map(|movement| {
+            Some(NewCashMovement {
+                kind: movement.kind.key()?,
+                amount: &movement.amount,
+                date: &movement.date,
+                portfolio_id,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    diesel::insert_into(cash_movements::table)
+        .values(&insertable_movements)
+        .execute(conn)
+}
+
+// Only deposits, withdrawals and fees are stored, trade settlements, incomes
+// and custody fees are taken from where they are already recorded
+pub fn cash_ledger(
+    conn: &PgConnection,
+    portfolio_id: i32,
+    until_date: NaiveDate,
+) -> QueryResult<Vec<CashEntry>> {
+    let movements = cash_movements::table
+        .select((
+            cash_movements::date,
+            cash_movements::kind,
+            cash_movements::amount,
+        ))
+        .filter(cash_movements::portfolio_id.eq(portfolio_id))
+        .filter(cash_movements::date.le(until_date))
+        .load::<(NaiveDate, String, BigDecimal)>(conn)?;
+
+    let trades = trades::table
+        .select((
+            trades::date,
+            trades::quantity,
+            trades::price,
+            trades::brokerage_fee + trades::emoluments,
+        ))
+        .filter(trades::portfolio_id.eq(portfolio_id))
+        .filter(trades::date.le(until_date))
+        .load::<(NaiveDate, BigDecimal, BigDecimal, BigDecimal)>(conn)?;
+
+    let incomes = incomes(conn, portfolio_id, until_date)?;
+    let custody_fees = custody_fees(conn, portfolio_id, until_date)?;
+
+    let mut ledger = movements
+        .into_iter()
+        .filter_map(|(date, kind, amount)| {
+            let kind = CashKind::from_key(&kind)?;
+
+            let amount = match kind {
+                CashKind::Deposit => amount,
+                _ => -amount,
+            };
+
+            Some(CashEntry { date, kind, amount })
+        })
+        .chain(
+            trades
+                .into_iter()
+                .map(|(date, quantity, price, fees)| CashEntry {
+                    amount: -(quantity * price) - fees,
+                    kind: CashKind::Trade,
+                    date,
+                }),
+        )
+        .chain(incomes.into_iter().map(|income| CashEntry {
+            kind: CashKind::Income,
+            amount: income.amount,
+            date: income.date,
+        }))
+        .chain(custody_fees.into_iter().map(|fee| CashEntry {
+            kind: CashKind::Fee,
+            amount: -fee.amount,
+            date: fee.date,
+        }))
+        .collect::<Vec<_>>();
+
+    ledger.sort_by_key(|entry| entry.date);
+
+    Ok(ledger)
+}
+
+pub fn cash_balance(ledger: &[CashEntry], date: NaiveDate) -> BigDecimal {
+    ledger
+        .iter()
+        .take_while(|entry| entry.date <= date)
+        .map(|entry| &entry.amount)
+        .sum()
+}
+
+// What was put in minus what was taken out, regardless of what was done with it
+pub fn net_contributions(ledger: &[CashEntry], date: NaiveDate) -> BigDecimal {
+    ledger
+        .iter()
+        .take_while(|entry| entry.date <= date)
+        .filter(|entry| entry.kind == CashKind::Deposit || entry.kind == CashKind::Withdrawal)
+        .map(|entry| &entry.amount)
+        .sum()
+}
@@ -0,0 +1,462 @@
+use crate::models::assets::{register_treasury_bond_asset, ticker_asset_id};
+use crate::models::benchmarks::{
+    benchmark_values, monthly_rate_covers, monthly_rate_index, Benchmark,
+};
+use crate::models::corporate_actions::{adjustment, corporate_actions, successor_asset_ids};
+use crate::models::taxes::{iof_rate, regressive_rate};
+use crate::schema::{asset_cash_events, asset_prices, trades};
+use bigdecimal::{BigDecimal, FromPrimitive, Zero};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use diesel::pg::upsert::excluded;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use std::collections::VecDeque;
+
+#[derive(Insertable)]
+#[table_name = "asset_cash_events"]
+struct NewAssetCashEvent<'a> {
+    asset_id: i32,
+    kind: &'static str,
+    date: &'a NaiveDate,
+    payment_date: &'a NaiveDate,
+    value: &'a BigDecimal,
+}
+
+#[derive(Queryable)]
+struct AssetCashEvent {
+    asset_id: i32,
+    kind: String,
+    date: NaiveDate,
+    payment_date: NaiveDate,
+    value: BigDecimal,
+}
+
+#[derive(Queryable)]
+struct Trade {
+    asset_id: i32,
+    date: NaiveDate,
+    quantity: BigDecimal,
+    price: BigDecimal,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum IncomeKind {
+    Distribution,
+    Dividend,
+    Jcp,
+    Coupon,
+    Redemption,
+}
+
+impl IncomeKind {
+    fn from_key(key: &str) -> Option<IncomeKind> {
+        match key {
+            "distribution" => Some(IncomeKind::Distribution),
+            "dividend" => Some(IncomeKind::Dividend),
+            "jcp" => Some(IncomeKind::Jcp),
+            "coupon" => Some(IncomeKind::Coupon),
+            "redemption" => Some(IncomeKind::Redemption),
+            _ => None,
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            IncomeKind::Distribution => "distribution",
+            IncomeKind::Dividend => "dividend",
+            IncomeKind::Jcp => "jcp",
+            IncomeKind::Coupon => "coupon",
+            IncomeKind::Redemption => "redemption",
+        }
+    }
+}
+
+// The amount is what was actually received, after any tax withheld at the source
+pub struct Income {
+    pub asset_id: i32,
+    pub kind: IncomeKind,
+    pub date: NaiveDate,
+    pub amount: BigDecimal,
+    pub withheld: BigDecimal,
+}
+
+// Values are per unit held at the end of the date, before any withholding
+pub struct IncomeEvent {
+    pub ticker: String,
+    pub kind: IncomeKind,
+    pub date: NaiveDate,
+    pub payment_date: NaiveDate,
+    pub value: BigDecimal,
+}
+
+pub fn register_income_events(conn: &PgConnection, events: &[IncomeEvent]) -> QueryResult<usize> {
+    conn.transaction(|| {
+        let mut new_cash_events = vec![];
+
+        for event in events {
+            new_cash_events.push(NewAssetCashEvent {
+                asset_id: ticker_asset_id(conn, &event.ticker)?,
+                kind: event.kind.key(),
+                date: &event.date,
+                payment_date: &event.payment_date,
+                value: &event.value,
+            });
+        }
+
+        upsert_cash_events(conn, &new_cash_events)
+    })
+}
+
+// Coupons are paid every six months counting back from maturity, over the nominal value
+// updated by the bond index since its base date. They are refreshed on every treasury
+// import, so their values catch up with index series imported later.
+pub fn register_treasury_bond_coupons(
+    conn: &PgConnection,
+    key: &'static str,
+    maturity_date: NaiveDate,
+    until_date: NaiveDate,
+) -> QueryResult<usize> {
+    let (yearly_rate, index) = match key {
+        "NTN-F" => (10.0, None),
+        "NTN-B" => (
+            6.0,
+            Some((Benchmark::Ipca, NaiveDate::from_ymd(2000, 7, 15))),
+        ),
+        // The NTN-C maturing in 2031 is the only one paying 12% a year
+        "NTN-C" if maturity_date.year() == 2031 => (
+            12.0,
+            Some((Benchmark::Igpm, NaiveDate::from_ymd(2000, 7, 1))),
+        ),
+        "NTN-C" => (
+            6.0,
+            Some((Benchmark::Igpm, NaiveDate::from_ymd(2000, 7, 1))),
+        ),
+        _ => return Ok(0),
+    };
+
+    conn.transaction(|| {
+        let asset_id = register_treasury_bond_asset(conn, key, maturity_date)?;
+
+        let first_date = asset_prices::table
+            .select(diesel::dsl::min(asset_prices::date))
+            .filter(asset_prices::asset_id.eq(asset_id))
+            .first::<Option<NaiveDate>>(conn)?;
+
+        let first_date = match first_date {
+            Some(first_date) => first_date,
+            None => return Ok(0),
+        };
+
+        let index_rates = match index {
+            Some((benchmark, _)) => benchmark_values(conn, benchmark, until_date)?,
+            None => vec![],
+        };
+
+        // Without the index since the base date the nominal value is unknown, and
+        // coupons are better left out than paid over the bare R$1000
+        if let Some((_, base_date)) = index {
+            if !monthly_rate_covers(&index_rates, base_date) {
+                return Ok(0);
+            }
+        }
+
+        let coupon_rate = (1.0 + yearly_rate / 100.0_f64).sqrt() - 1.0;
+
+        let mut coupons = vec![];
+        let mut months = 0;
+
+        loop {
+            let coupon_date = months_before(maturity_date, months);
+            months += 6;
+
+            if coupon_date < first_date {
+                break;
+            }
+
+            if coupon_date > until_date {
+                continue;
+            }
+
+            let nominal_value = match index {
+                Some((_, base_date)) => {
+                    1000.0 * monthly_rate_index(&index_rates, coupon_date)
+                        / monthly_rate_index(&index_rates, base_date)
+                }
+                None => 1000.0,
+            };
+
+            let value = match BigDecimal::from_f64(nominal_value * coupon_rate) {
+                Some(value) => value.with_scale(6),
+                None => continue,
+            };
+
+            coupons.push((coupon_date.pred(), next_business_day(coupon_date), value));
+        }
+
+        let new_cash_events = coupons
+            .iter()
+            .map(|(date, payment_date, value)| NewAssetCashEvent {
+                kind: IncomeKind::Coupon.key(),
+                asset_id,
+                date,
+                payment_date,
+                value,
+            })
+            .collect::<Vec<_>>();
+
+        upsert_cash_events(conn, &new_cash_events)
+    })
+}
+
+// Matured bonds are settled at their last price on the maturity date, which is then
+// paid out to whoever still held them
+pub fn register_treasury_bond_redemption(
+    conn: &PgConnection,
+    key: &'static str,
+    maturity_date: NaiveDate,
+    until_date: NaiveDate,
+) -> QueryResult<usize> {
+    if maturity_date > until_date {
+        return Ok(0);
+    }
+
+    conn.transaction(|| {
+        let asset_id = register_treasury_bond_asset(conn, key, maturity_date)?;
+
+        let price = asset_prices::table
+            .select(asset_prices::price)
+            .filter(asset_prices::asset_id.eq(asset_id))
+            .filter(asset_prices::date.le(maturity_date))
+            .order(asset_prices::date.desc())
+            .first::<BigDecimal>(conn)
+            .optional()?;
+
+        let price = match price {
+            Some(price) => price,
+            None => return Ok(0),
+        };
+
+        upsert_cash_events(
+            conn,
+            &[NewAssetCashEvent {
+                kind: IncomeKind::Redemption.key(),
+                asset_id,
+                date: &maturity_date.pred(),
+                payment_date: &next_business_day(maturity_date),
+                value: &price,
+            }],
+        )
+    })
+}
+
+pub fn redemptions(
+    conn: &PgConnection,
+    asset_ids: &[i32],
+    until_date: NaiveDate,
+) -> QueryResult<Vec<(i32, NaiveDate, BigDecimal)>> {
+    asset_cash_events::table
+        .select((
+            asset_cash_events::asset_id,
+            asset_cash_events::payment_date,
+            asset_cash_events::value,
+        ))
+        .filter(asset_cash_events::kind.eq(IncomeKind::Redemption.key()))
+        .filter(asset_cash_events::asset_id.eq_any(asset_ids))
+        .filter(asset_cash_events::payment_date.le(until_date))
+        .load(conn)
+}
+
+fn upsert_cash_events(
+    conn: &PgConnection,
+    new_cash_events: &[NewAssetCashEvent],
+) -> QueryResult<usize> {
+    diesel::insert_into(asset_cash_events::table)
+        .values(new_cash_events)
+        .on_conflict((
+            asset_cash_events::asset_id,
+            asset_cash_events::kind,
+            asset_cash_events::date,
+        ))
+        .do_update()
+        .set((
+            asset_cash_events::payment_date.eq(excluded(asset_cash_events::payment_date)),
+            asset_cash_events::value.eq(excluded(asset_cash_events::value)),
+        ))
+        .execute(conn)
+}
+
+// Events are paid to whoever held the asset at the end of their date (data com)
+pub fn incomes(
+    conn: &PgConnection,
+    portfolio_id: i32,
+    until_date: NaiveDate,
+) -> QueryResult<Vec<Income>> {
+    let trades = trades::table
+        .select((
+            trades::asset_id,
+            trades::date,
+            trades::quantity,
+            trades::price,
+        ))
+        .filter(trades::portfolio_id.eq(portfolio_id))
+        .filter(trades::date.le(until_date))
+        .order((trades::date, trades::id))
+        .load::<Trade>(conn)?;
+
+    let mut asset_ids = trades
+        .iter()
+        .map(|trade| trade.asset_id)
+        .collect::<Vec<_>>();
+
+    asset_ids.sort_unstable();
+    asset_ids.dedup();
+
+    let actions = corporate_actions(conn, until_date)?;
+    let asset_ids = successor_asset_ids(&actions, &asset_ids);
+
+    let cash_events = asset_cash_events::table
+        .select((
+            asset_cash_events::asset_id,
+            asset_cash_events::kind,
+            asset_cash_events::date,
+            asset_cash_events::payment_date,
+            asset_cash_events::value,
+        ))
+        .filter(asset_cash_events::asset_id.eq_any(&asset_ids))
+        .filter(asset_cash_events::payment_date.le(until_date))
+        .order((asset_cash_events::payment_date, asset_cash_events::asset_id))
+        .load::<AssetCashEvent>(conn)?;
+
+    // Units held are counted as the asset paying the event, after any corporate action
+    // taken up to its date, and incomes are attributed to the asset it became since
+    Ok(cash_events
+        .into_iter()
+        .filter_map(|cash_event| {
+            let quantity = trades
+                .iter()
+                .filter(|trade| trade.date <= cash_event.date)
+                .filter_map(|trade| {
+                    let (asset_id, ratio) =
+                        adjustment(&actions, trade.asset_id, trade.date, cash_event.date);
+
+                    if asset_id == cash_event.asset_id {
+                        Some(&trade.quantity * ratio)
+                    } else {
+                        None
+                    }
+                })
+                .sum::<BigDecimal>();
+
+            if quantity <= BigDecimal::zero() {
+                return None;
+            }
+
+            let kind = IncomeKind::from_key(&cash_event.kind)?;
+
+            let withheld = match kind {
+                IncomeKind::Coupon => coupon_withholding(&trades, &cash_event),
+                IncomeKind::Redemption => redemption_withholding(&trades, &cash_event),
+                IncomeKind::Jcp => (&quantity * &cash_event.value * jcp_rate()).with_scale(2),
+                IncomeKind::Distribution | IncomeKind::Dividend => BigDecimal::zero(),
+            };
+
+            let (asset_id, _) =
+                adjustment(&actions, cash_event.asset_id, cash_event.date, until_date);
+
+            Some(Income {
+                asset_id,
+                kind,
+                date: cash_event.payment_date,
+                amount: &quantity * &cash_event.value - &withheld,
+                withheld,
+            })
+        })
+        .collect())
+}
+
+// JCP (juros sobre capital próprio) is taxed at the source with a flat rate
+fn jcp_rate() -> BigDecimal {
+    BigDecimal::from(15) / BigDecimal::from(100)
+}
+
+// Coupons are taxed at the source with the regressive rates, lot by lot
+// in the order they were bought
+fn coupon_withholding(trades: &[Trade], cash_event: &AssetCashEvent) -> BigDecimal {
+    held_lots(trades, cash_event)
+        .iter()
+        .map(|(lot_date, lot_quantity, _)| {
+            let days = (cash_event.payment_date - *lot_date).num_days();
+            lot_quantity * &cash_event.value * regressive_rate(days)
+        })
+        .sum::<BigDecimal>()
+        .with_scale(2)
+}
+
+// Redemptions are taxed like sells, over the gain of each lot still held
+fn redemption_withholding(trades: &[Trade], cash_event: &AssetCashEvent) -> BigDecimal {
+    held_lots(trades, cash_event)
+        .iter()
+        .filter(|(_, _, lot_price)| cash_event.value > *lot_price)
+        .map(|(lot_date, lot_quantity, lot_price)| {
+            let days = (cash_event.payment_date - *lot_date).num_days();
+            let gain = (&cash_event.value - lot_price) * lot_quantity;
+            let iof = &gain * iof_rate(days);
+            (&gain - &iof) * regressive_rate(days) + iof
+        })
+        .sum::<BigDecimal>()
+        .with_scale(2)
+}
+
+fn held_lots(
+    trades: &[Trade],
+    cash_event: &AssetCashEvent,
+) -> VecDeque<(NaiveDate, BigDecimal, BigDecimal)> {
+    let mut lots = VecDeque::<(NaiveDate, BigDecimal, BigDecimal)>::new();
+
+    for trade in trades
+        .iter()
+        .filter(|trade| trade.asset_id == cash_event.asset_id && trade.date <= cash_event.date)
+    {
+        if trade.quantity > BigDecimal::zero() {
+            lots.push_back((trade.date, trade.quantity.clone(), trade.price.clone()));
+            continue;
+        }
+
+        let mut quantity = -&trade.quantity;
+
+        while quantity > BigDecimal::zero() {
+            let (_, lot_quantity, _) = match lots.front_mut() {
+                Some(lot) => lot,
+                None => break,
+            };
+
+            if *lot_quantity > quantity {
+                *lot_quantity -= &quantity;
+                break;
+            }
+
+            quantity -= &*lot_quantity;
+            lots.pop_front();
+        }
+    }
+
+    lots
+}
+
+// Days past the end of the shorter month fall on its last day
+fn months_before(date: NaiveDate, months: u32) -> NaiveDate {
+    let total_months = date.year() * 12 + date.month0() as i32 - months as i32;
+    let (year, month) = (total_months / 12, total_months as u32 % 12 + 1);
+
+    NaiveDate::from_ymd_opt(year, month, date.day()).unwrap_or_else(|| {
+        let next_month = total_months + 1;
+        NaiveDate::from_ymd(next_month / 12, next_month as u32 % 12 + 1, 1).pred()
+    })
+}
+
+fn next_business_day(mut date: NaiveDate) -> NaiveDate {
+    while date.weekday() == Weekday::Sat || date.weekday() == Weekday::Sun {
+        date += Duration::days(1);
+    }
+    date
+}
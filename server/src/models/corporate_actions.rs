@@ -0,0 +1,155 @@
+use crate::models::assets::ticker_asset_id;
+use crate::schema::corporate_actions;
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use diesel::pg::upsert::excluded;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+
+#[derive(Insertable)]
+#[table_name = "corporate_actions"]
+struct NewCorporateAction<'a> {
+    asset_id: i32,
+    kind: &'static str,
+    date: &'a NaiveDate,
+    ratio: &'a BigDecimal,
+    new_asset_id: Option<i32>,
+}
+
+#[derive(Queryable)]
+pub struct AssetCorporateAction {
+    asset_id: i32,
+    date: NaiveDate,
+    ratio: BigDecimal,
+    new_asset_id: Option<i32>,
+}
+
+#[derive(Clone, Copy)]
+pub enum CorporateActionKind {
+    Split,
+    ReverseSplit,
+    Rename,
+    Merger,
+}
+
+impl CorporateActionKind {
+    fn key(self) -> &'static str {
+        match self {
+            CorporateActionKind::Split => "split",
+            CorporateActionKind::ReverseSplit => "reverse_split",
+            CorporateActionKind::Rename => "rename",
+            CorporateActionKind::Merger => "merger",
+        }
+    }
+}
+
+// The ratio is how many units are held after the action for each unit held before it,
+// and renames and mergers carry the ticker of the asset held from then on
+pub struct CorporateAction {
+    pub ticker: String,
+    pub kind: CorporateActionKind,
+    pub date: NaiveDate,
+    pub ratio: BigDecimal,
+    pub new_ticker: Option<String>,
+}
+
+pub fn register_corporate_actions(
+    conn: &PgConnection,
+    actions: &[CorporateAction],
+) -> QueryResult<usize> {
+    conn.transaction(|| {
+        let mut new_actions = vec![];
+
+        for action in actions {
+            let new_asset_id = match &action.new_ticker {
+                Some(new_ticker) => Some(ticker_asset_id(conn, new_ticker)?),
+                None => None,
+            };
+
+            new_actions.push(NewCorporateAction {
+                asset_id: ticker_asset_id(conn, &action.ticker)?,
+                kind: action.kind.key(),
+                date: &action.date,
+                ratio: &action.ratio,
+                new_asset_id,
+            });
+        }
+
+        diesel::insert_into(corporate_actions::table)
+            .values(&new_actions)
+            .on_conflict((
+                corporate_actions::asset_id,
+                corporate_actions::date,
+                corporate_actions::kind,
+            ))
+            .do_update()
+            .set((
+                corporate_actions::ratio.eq(excluded(corporate_actions::ratio)),
+                corporate_actions::new_asset_id.eq(excluded(corporate_actions::new_asset_id)),
+            ))
+            .execute(conn)
+    })
+}
+
+pub fn corporate_actions(
+    conn: &PgConnection,
+    until_date: NaiveDate,
+) -> QueryResult<Vec<AssetCorporateAction>> {
+    corporate_actions::table
+        .select((
+            corporate_actions::asset_id,
+            corporate_actions::date,
+            corporate_actions::ratio,
+            corporate_actions::new_asset_id,
+        ))
+        .filter(corporate_actions::date.le(until_date))
+        .order((corporate_actions::date, corporate_actions::id))
+        .load(conn)
+}
+
+// Follows the actions taken on an asset after a date and up to another one, returning
+// the asset it became and how many units of it each original unit turned into.
+// Quantities are multiplied by the ratio and unit prices divided by it.
+pub fn adjustment(
+    actions: &[AssetCorporateAction],
+    asset_id: i32,
+    from_date: NaiveDate,
+    until_date: NaiveDate,
+) -> (i32, BigDecimal) {
+    let mut asset_id = asset_id;
+    let mut ratio = BigDecimal::from(1);
+
+    for action in actions
+        .iter()
+        .filter(|action| action.date > from_date && action.date <= until_date)
+    {
+        if action.asset_id != asset_id {
+            continue;
+        }
+
+        ratio = &ratio * &action.ratio;
+
+        if let Some(new_asset_id) = action.new_asset_id {
+            asset_id = new_asset_id;
+        }
+    }
+
+    (asset_id, ratio)
+}
+
+// Along with the given assets, every asset they became through renames and mergers
+pub fn successor_asset_ids(actions: &[AssetCorporateAction], asset_ids: &[i32]) -> Vec<i32> {
+    let mut successor_asset_ids = asset_ids.to_vec();
+
+    for action in actions {
+        if let Some(new_asset_id) = action.new_asset_id {
+            if successor_asset_ids.contains(&action.asset_id) {
+                successor_asset_ids.push(new_asset_id);
+            }
+        }
+    }
+
+    successor_asset_ids.sort_unstable();
+    successor_asset_ids.dedup();
+    successor_asset_ids
+}
@@ -0,0 +1,146 @@
+use crate::models::cash_events::redemptions;
+use crate::models::corporate_actions::{adjustment, corporate_actions};
+use crate::schema::trades;
+use bigdecimal::{BigDecimal, Zero};
+use chrono::NaiveDate;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use itertools::Itertools;
+
+#[derive(Clone)]
+pub struct Sell {
+    pub date: NaiveDate,
+    pub quantity: BigDecimal,
+    pub price: BigDecimal,
+    pub average_price: BigDecimal,
+    pub profit: BigDecimal,
+}
+
+pub struct CostBasis {
+    pub asset_id: i32,
+    pub quantity: BigDecimal,
+    pub average_price: BigDecimal,
+    pub cost: BigDecimal,
+    pub realized_profit: BigDecimal,
+    pub sells: Vec<Sell>,
+}
+
+#[derive(Queryable)]
+struct Trade {
+    asset_id: i32,
+    date: NaiveDate,
+    quantity: BigDecimal,
+    price: BigDecimal,
+    fees: BigDecimal,
+}
+
+pub fn cost_bases(
+    conn: &PgConnection,
+    portfolio_id: i32,
+    date: NaiveDate,
+) -> QueryResult<Vec<CostBasis>> {
+    let mut trades = trades::table
+        .select((
+            trades::asset_id,
+            trades::date,
+            trades::quantity,
+            trades::price,
+            trades::brokerage_fee + trades::emoluments,
+        ))
+        .filter(trades::portfolio_id.eq(portfolio_id))
+        .filter(trades::date.le(date))
+        .order((trades::asset_id, trades::date, trades::id))
+        .load::<Trade>(conn)?;
+
+    // Trades are restated as the asset they became after splits, renames and mergers
+    let actions = corporate_actions(conn, date)?;
+
+    for trade in &mut trades {
+        let (asset_id, ratio) = adjustment(&actions, trade.asset_id, trade.date, date);
+        trade.asset_id = asset_id;
+        trade.quantity = &trade.quantity * &ratio;
+        trade.price = &trade.price / &ratio;
+    }
+
+    trades.sort_by_key(|trade| (trade.asset_id, trade.date));
+
+    let mut asset_ids = trades
+        .iter()
+        .map(|trade| trade.asset_id)
+        .collect::<Vec<_>>();
+
+    asset_ids.dedup();
+
+    // Matured bonds still held are sold back at their redemption value
+    for (asset_id, payment_date, value) in redemptions(conn, &asset_ids, date)? {
+        let quantity = trades
+            .iter()
+            .filter(|trade| trade.asset_id == asset_id && trade.date < payment_date)
+            .map(|trade| &trade.quantity)
+            .sum::<BigDecimal>();
+
+        if quantity > BigDecimal::zero() {
+            trades.push(Trade {
+                date: payment_date,
+                quantity: -quantity,
+                price: value,
+                fees: BigDecimal::zero(),
+                asset_id,
+            });
+        }
+    }
+
+    trades.sort_by_key(|trade| (trade.asset_id, trade.date));
+
+    Ok(trades
+        .iter()
+        .group_by(|trade| trade.asset_id)
+        .into_iter()
+        .map(|(asset_id, trades)| walk(asset_id, trades))
+        .collect())
+}
+
+// Brazilian average cost (preço médio): buys are averaged into the cost,
+// while sells take their share of it out and realize the difference.
+// Fees paid are added to the cost of buys and taken from the proceeds of sells.
+fn walk<'a>(asset_id: i32, trades: impl Iterator<Item = &'a Trade>) -> CostBasis {
+    let mut cost_basis = CostBasis {
+        asset_id,
+        quantity: BigDecimal::zero(),
+        average_price: BigDecimal::zero(),
+        cost: BigDecimal::zero(),
+        realized_profit: BigDecimal::zero(),
+        sells: vec![],
+    };
+
+    for trade in trades {
+        if trade.quantity > BigDecimal::zero() {
+            cost_basis.cost += &trade.quantity * &trade.price + &trade.fees;
+            cost_basis.quantity += &trade.quantity;
+            cost_basis.average_price = &cost_basis.cost / &cost_basis.quantity;
+            continue;
+        }
+
+        let quantity = -&trade.quantity;
+        let profit = (&trade.price - &cost_basis.average_price) * &quantity - &trade.fees;
+
+        cost_basis.cost -= &cost_basis.average_price * &quantity;
+        cost_basis.quantity -= &quantity;
+        cost_basis.realized_profit += &profit;
+
+        cost_basis.sells.push(Sell {
+            date: trade.date,
+            price: trade.price.clone(),
+            average_price: cost_basis.average_price.clone(),
+            quantity,
+            profit,
+        });
+
+        if cost_basis.quantity.is_zero() {
+            cost_basis.cost = BigDecimal::zero();
+            cost_basis.average_price = BigDecimal::zero();
+        }
+    }
+
+    cost_basis
+}
@@ -0,0 +1,113 @@
+use crate::models::cash_events::redemptions;
+use crate::schema::{asset_prices, trades, treasury_bonds};
+use bigdecimal::{BigDecimal, FromPrimitive, Zero};
+use chrono::NaiveDate;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+
+pub struct CustodyFee {
+    pub asset_id: i32,
+    pub date: NaiveDate,
+    pub amount: BigDecimal,
+}
+
+// B3 charges a yearly percentage over the value of treasury bonds held in custody
+fn treasury_bond_custody_rate() -> f64 {
+    0.20
+}
+
+// The fee accrues every calendar day over the bonds held at their latest price,
+// until they are sold or redeemed
+pub fn custody_fees(
+    conn: &PgConnection,
+    portfolio_id: i32,
+    until_date: NaiveDate,
+) -> QueryResult<Vec<CustodyFee>> {
+    let trades = trades::table
+        .inner_join(treasury_bonds::table.on(treasury_bonds::id.eq(trades::asset_id)))
+        .select((trades::asset_id, trades::date, trades::quantity))
+        .filter(trades::portfolio_id.eq(portfolio_id))
+        .filter(trades::date.le(until_date))
+        .order((trades::asset_id, trades::date))
+        .load::<(i32, NaiveDate, BigDecimal)>(conn)?;
+
+    let mut asset_ids = trades
+        .iter()
+        .map(|(asset_id, _, _)| *asset_id)
+        .collect::<Vec<_>>();
+
+    asset_ids.dedup();
+
+    let prices = asset_prices::table
+        .select((
+            asset_prices::asset_id,
+            asset_prices::date,
+            asset_prices::price,
+        ))
+        .filter(asset_prices::asset_id.eq_any(&asset_ids))
+        .filter(asset_prices::date.le(until_date))
+        .order((asset_prices::asset_id, asset_prices::date))
+        .load::<(i32, NaiveDate, BigDecimal)>(conn)?;
+
+    let redemptions = redemptions(conn, &asset_ids, until_date)?;
+
+    let daily_rate = BigDecimal::from_f64(treasury_bond_custody_rate() / 100.0 / 365.0)
+        .unwrap_or_else(BigDecimal::zero);
+
+    let mut fees = vec![];
+
+    for asset_id in asset_ids {
+        let mut asset_trades = trades
+            .iter()
+            .filter(|(trade_asset_id, _, _)| *trade_asset_id == asset_id)
+            .peekable();
+
+        let mut asset_prices = prices
+            .iter()
+            .filter(|(price_asset_id, _, _)| *price_asset_id == asset_id)
+            .peekable();
+
+        let last_date = redemptions
+            .iter()
+            .find(|(redeemed_asset_id, _, _)| *redeemed_asset_id == asset_id)
+            .map_or(until_date, |(_, payment_date, _)| payment_date.pred());
+
+        let mut date = match asset_trades.peek() {
+            Some((_, first_date, _)) => *first_date,
+            None => continue,
+        };
+
+        let mut quantity = BigDecimal::zero();
+        let mut price = BigDecimal::zero();
+
+        while date <= last_date {
+            while let Some((_, trade_date, trade_quantity)) = asset_trades.peek() {
+                if *trade_date > date {
+                    break;
+                }
+                quantity += trade_quantity;
+                asset_trades.next();
+            }
+
+            while let Some((_, price_date, latest_price)) = asset_prices.peek() {
+                if *price_date > date {
+                    break;
+                }
+                price = latest_price.clone();
+                asset_prices.next();
+            }
+
+            if quantity > BigDecimal::zero() {
+                fees.push(CustodyFee {
+                    amount: (&quantity * &price * &daily_rate).with_scale(8),
+                    asset_id,
+                    date,
+                });
+            }
+
+            date = date.succ();
+        }
+    }
+
+    Ok(fees)
+}
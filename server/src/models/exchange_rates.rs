@@ -0,0 +1,163 @@
+use crate::schema::{cryptos, exchange_rates};
+use bigdecimal::{BigDecimal, Zero};
+use chrono::NaiveDate;
+use diesel::pg::upsert::excluded;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Currency {
+    Brl,
+    Usd,
+}
+
+impl Currency {
+    pub fn from_key(key: &str) -> Option<Currency> {
+        match key {
+            "BRL" => Some(Currency::Brl),
+            "USD" => Some(Currency::Usd),
+            _ => None,
+        }
+    }
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Currency::Brl => "BRL",
+            Currency::Usd => "USD",
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[table_name = "exchange_rates"]
+struct NewExchangeRate<'a> {
+    currency: &'static str,
+    date: &'a NaiveDate,
+    rate: &'a BigDecimal,
+}
+
+// Rates are how many reais one unit of the currency was worth at the date
+pub fn register_exchange_rates(
+    conn: &PgConnection,
+    currency: Currency,
+    rates: Vec<(NaiveDate, BigDecimal)>,
+) -> QueryResult<usize> {
+    let insertable_rates: Vec<_> = rates
+        .iter()
+        .map(|(date, rate)| NewExchangeRate {
+            currency: currency.key(),
+            date,
+            rate,
+        })
+        .collect();
+
+    diesel::insert_into(exchange_rates::table)
+        .values(&insertable_rates)
+        .on_conflict((exchange_rates::currency, exchange_rates::date))
+        .do_update()
+        .set(exchange_rates::rate.eq(excluded(exchange_rates::rate)))
+        .execute(conn)
+}
+
+pub fn brl_rate(
+    conn: &PgConnection,
+    currency: Currency,
+    date: NaiveDate,
+) -> QueryResult<BigDecimal> {
+    if currency == Currency::Brl {
+        return Ok(BigDecimal::from(1));
+    }
+
+    exchange_rates::table
+        .select(exchange_rates::rate)
+        .filter(exchange_rates::currency.eq(currency.key()))
+        .filter(exchange_rates::date.le(date))
+        .order(exchange_rates::date.desc())
+        .first(conn)
+}
+
+// Prices are stored in the currency they are quoted in, so the foreign ones are
+// converted with the latest rate known at their date. Without one, they are zeroed.
+pub fn brl_prices(
+    conn: &PgConnection,
+    mut prices: Vec<(i32, NaiveDate, BigDecimal)>,
+) -> QueryResult<Vec<(i32, NaiveDate, BigDecimal)>> {
+    let asset_ids = prices
+        .iter()
+        .map(|(asset_id, _, _)| *asset_id)
+        .collect::<Vec<_>>();
+
+    let foreign_assets = cryptos::table
+        .select((cryptos::id, cryptos::currency))
+        .filter(cryptos::id.eq_any(&asset_ids))
+        .filter(cryptos::currency.ne(Currency::Brl.key()))
+        .load::<(i32, String)>(conn)?;
+
+    // Foreign prices are walked by currency and date, along with the rates in the same order
+    let mut foreign_prices = prices
+        .iter()
+        .enumerate()
+        .filter_map(|(index, (asset_id, date, _))| {
+            foreign_assets
+                .iter()
+                .find(|(id, _)| id == asset_id)
+                .map(|(_, currency)| (currency.as_str(), *date, index))
+        })
+        .collect::<Vec<_>>();
+
+    let last_date = match foreign_prices.iter().map(|(_, date, _)| *date).max() {
+        Some(last_date) => last_date,
+        None => return Ok(prices),
+    };
+
+    foreign_prices.sort_unstable();
+
+    let mut currencies = foreign_prices
+        .iter()
+        .map(|(currency, _, _)| *currency)
+        .collect::<Vec<_>>();
+
+    currencies.dedup();
+
+    let rates = exchange_rates::table
+        .select((
+            exchange_rates::currency,
+            exchange_rates::date,
+            exchange_rates::rate,
+        ))
+        .filter(exchange_rates::currency.eq_any(&currencies))
+        .filter(exchange_rates::date.le(last_date))
+        .order((exchange_rates::currency, exchange_rates::date))
+        .load::<(String, NaiveDate, BigDecimal)>(conn)?;
+
+    let mut rates = rates.iter().peekable();
+    let mut rate_currency = "";
+    let mut rate = None;
+
+    for (currency, date, index) in foreign_prices {
+        if currency != rate_currency {
+            rate_currency = currency;
+            rate = None;
+        }
+
+        while let Some((next_currency, next_date, next_rate)) = rates.peek() {
+            if (next_currency.as_str(), *next_date) > (currency, date) {
+                break;
+            }
+
+            if next_currency == currency {
+                rate = Some(next_rate);
+            }
+
+            rates.next();
+        }
+
+        let price = &mut prices[index].2;
+        *price = match rate {
+            Some(rate) => &*price * rate,
+            None => BigDecimal::zero(),
+        };
+    }
+
+    Ok(prices)
+}
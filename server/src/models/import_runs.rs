@@ -0,0 +1,264 @@
+use crate::models::prices::PriceCounts;
+use crate::schema::{import_run_assets, import_runs};
+use chrono::{NaiveDateTime, Utc};
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+
+#[derive(Insertable)]
+#[table_name = "import_run_assets"]
+struct NewImportRunAsset<'a> {
+    import_run_id: i32,
+    asset: &'a str,
+    inserted: i32,
+    updated: i32,
+    unchanged: i32,
+    error: Option<&'a str>,
+}
+
+#[derive(Clone, Copy)]
+pub enum ImportOutcome {
+    Succeeded,
+    Partial,
+    Failed,
+}
+
+impl ImportOutcome {
+    fn from_key(key: &str) -> Option<ImportOutcome> {
+        match key {
+            "succeeded" => Some(ImportOutcome::Succeeded),
+            "partial" => Some(ImportOutcome::Partial),
+            "failed" => Some(ImportOutcome::Failed),
+            _ => None,
+        }
+    }
+
+    pub fn key(self) -> &'static str {
+        match self {
+            ImportOutcome::Succeeded => "succeeded",
+            ImportOutcome::Partial => "partial",
+            ImportOutcome::Failed => "failed",
+        }
+    }
+}
+
+// The asset is labeled the way its importer identifies it, like a ticker or a fund CNPJ
+pub struct ImportRunAsset {
+    pub asset: String,
+    pub result: Result<PriceCounts, String>,
+}
+
+// Runs without an outcome are either still going or were interrupted before finishing
+pub struct ImportRun {
+    pub id: i32,
+    pub source: String,
+    pub started_at: NaiveDateTime,
+    pub finished_at: Option<NaiveDateTime>,
+    pub outcome: Option<ImportOutcome>,
+    pub error: Option<String>,
+    pub assets: Vec<ImportRunAsset>,
+}
+
+// Every price written by an importer is traced back to the run that wrote it
+pub fn register_import_run(conn: &PgConnection, source: &str) -> QueryResult<i32> {
+    diesel::insert_into(import_runs::table)
+        .values(import_runs::source.eq(source))
+        .returning(import_runs::id)
+        .get_result(conn)
+}
+
+type AssetResult<A, E> = (A, Result<PriceCounts, E>);
+
+// The importer describes its own errors and labels its own assets, which it keeps as the
+// results it returns. Whatever it wrote is there already, so failing to finish the run
+// is only logged instead of failing the import.
+pub fn finish_import_run<A, E>(
+    conn: &PgConnection,
+    import_run_id: i32,
+    result: Result<&[AssetResult<A, E>], String>,
+    label: impl Fn(&A) -> String,
+    message: impl Fn(&E) -> String,
+) {
+    let assets = result.map(|results| {
+        results
+            .iter()
+            .map(|(asset, result)| ImportRunAsset {
+                asset: label(asset),
+                result: match result {
+                    Ok(counts) => Ok(*counts),
+                    Err(e) => Err(message(e)),
+                },
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let finished = finish(
+        conn,
+        import_run_id,
+        assets.as_ref().map(Vec::as_slice).map_err(String::as_str),
+    );
+
+    if let Err(e) = finished {
+        log::error!("Import run {} could not be finished: {}", import_run_id, e);
+    }
+}
+
+// Importers of anything other than prices have no assets to account for, only an outcome
+pub fn finish_assetless_import_run(
+    conn: &PgConnection,
+    import_run_id: i32,
+    result: Result<(), String>,
+) {
+    let finished = finish(
+        conn,
+        import_run_id,
+        result.as_ref().map(|_| &[][..]).map_err(String::as_str),
+    );
+
+    if let Err(e) = finished {
+        log::error!("Import run {} could not be finished: {}", import_run_id, e);
+    }
+}
+
+// A run fails when it couldn't get to the assets or when every one of them failed,
+// and is partial when only some of them did
+fn finish(
+    conn: &PgConnection,
+    import_run_id: i32,
+    result: Result<&[ImportRunAsset], &str>,
+) -> QueryResult<()> {
+    let (outcome, error) = match result {
+        Err(error) => (ImportOutcome::Failed, Some(error)),
+        Ok(assets) => {
+            let failed_count = assets.iter().filter(|a| a.result.is_err()).count();
+
+            if failed_count == 0 {
+                (ImportOutcome::Succeeded, None)
+            } else if failed_count == assets.len() {
+                (ImportOutcome::Failed, None)
+            } else {
+                (ImportOutcome::Partial, None)
+            }
+        }
+    };
+
+    conn.transaction(|| {
+        if let Ok(assets) = result {
+            let new_assets: Vec<_> = assets
+                .iter()
+                .map(|a| match &a.result {
+                    Ok(counts) => NewImportRunAsset {
+                        import_run_id,
+                        asset: &a.asset,
+                        inserted: counts.inserted as i32,
+                        updated: counts.updated as i32,
+                        unchanged: counts.unchanged as i32,
+                        error: None,
+                    },
+                    Err(error) => NewImportRunAsset {
+                        import_run_id,
+                        asset: &a.asset,
+                        inserted: 0,
+                        updated: 0,
+                        unchanged: 0,
+                        error: Some(error),
+                    },
+                })
+                .collect();
+
+            if !new_assets.is_empty() {
+                diesel::insert_into(import_run_assets::table)
+                    .values(&new_assets)
+                    .execute(conn)?;
+            }
+        }
+
+        diesel::update(import_runs::table.filter(import_runs::id.eq(import_run_id)))
+            .set((
+                import_runs::finished_at.eq(Utc::now().naive_utc()),
+                import_runs::outcome.eq(outcome.key()),
+                import_runs::error.eq(error),
+            ))
+            .execute(conn)?;
+
+        Ok(())
+    })
+}
+
+// The latest runs first, optionally only the ones of a source
+pub fn import_runs(
+    conn: &PgConnection,
+    source: Option<&str>,
+    limit: i64,
+) -> QueryResult<Vec<ImportRun>> {
+    let mut query = import_runs::table
+        .select((
+            import_runs::id,
+            import_runs::source,
+            import_runs::started_at,
+            import_runs::finished_at,
+            import_runs::outcome,
+            import_runs::error,
+        ))
+        .order(import_runs::id.desc())
+        .limit(limit)
+        .into_boxed();
+
+    if let Some(source) = source {
+        query = query.filter(import_runs::source.eq(source));
+    }
+
+    let runs = query.load::<(
+        i32,
+        String,
+        NaiveDateTime,
+        Option<NaiveDateTime>,
+        Option<String>,
+        Option<String>,
+    )>(conn)?;
+
+    let run_ids: Vec<_> = runs.iter().map(|(id, _, _, _, _, _)| *id).collect();
+
+    let assets = import_run_assets::table
+        .select((
+            import_run_assets::import_run_id,
+            import_run_assets::asset,
+            import_run_assets::inserted,
+            import_run_assets::updated,
+            import_run_assets::unchanged,
+            import_run_assets::error,
+        ))
+        .filter(import_run_assets::import_run_id.eq_any(&run_ids))
+        .order((import_run_assets::import_run_id, import_run_assets::id))
+        .load::<(i32, String, i32, i32, i32, Option<String>)>(conn)?;
+
+    Ok(runs
+        .into_iter()
+        .map(
+            |(id, source, started_at, finished_at, outcome, error)| ImportRun {
+                assets: assets
+                    .iter()
+                    .filter(|(import_run_id, _, _, _, _, _)| *import_run_id == id)
+                    .map(
+                        |(_, asset, inserted, updated, unchanged, error)| ImportRunAsset {
+                            asset: asset.clone(),
+                            result: match error {
+                                Some(error) => Err(error.clone()),
+                                None => Ok(PriceCounts {
+                                    inserted: *inserted as usize,
+                                    updated: *updated as usize,
+                                    unchanged: *unchanged as usize,
+                                }),
+                            },
+                        },
+                    )
+                    .collect(),
+                outcome: outcome.as_deref().and_then(ImportOutcome::from_key),
+                id,
+                source,
+                started_at,
+                finished_at,
+                error,
+            },
+        )
+        .collect())
+}
@@ -1,22 +1,35 @@
 use crate::models::assets::{retrieve_assetables, Assetable};
+use crate::models::cash::{cash_balance, cash_ledger};
+use crate::models::cash_events::{incomes, redemptions, IncomeKind};
+use crate::models::cost_bases::{cost_bases, Sell};
 use crate::models::prices::latest_prices;
-use crate::schema::trades;
+use crate::models::treasury_bond_rates::treasury_bond_rates;
 use bigdecimal::{BigDecimal, Zero};
 use chrono::NaiveDate;
 use diesel::pg::PgConnection;
 use diesel::prelude::*;
-use itertools::Itertools;
 
 pub struct AssetPosition {
     pub assetable: Assetable,
     pub price: BigDecimal,
     pub quantity: BigDecimal,
     pub amount: BigDecimal,
+    pub average_price: BigDecimal,
+    pub cost: BigDecimal,
+    pub realized_profit: BigDecimal,
+    pub unrealized_profit: BigDecimal,
+    pub income: BigDecimal,
+    pub sells: Vec<Sell>,
+    pub contracted_rate: Option<BigDecimal>,
+    pub market_rate: Option<BigDecimal>,
 }
 
 pub struct PortfolioPosition {
     pub amount: BigDecimal,
+    pub income: BigDecimal,
+    pub cash: BigDecimal,
     pub assets: Vec<AssetPosition>,
+    pub settled: Vec<AssetPosition>,
 }
 
 pub fn position(
@@ -24,44 +37,80 @@ pub fn position(
     portfolio_id: i32,
     date: NaiveDate,
 ) -> QueryResult<PortfolioPosition> {
-    let trades = trades::table
-        .select((trades::asset_id, trades::quantity))
-        .filter(trades::portfolio_id.eq(portfolio_id))
-        .filter(trades::date.le(date))
-        .order(trades::asset_id)
-        .load::<(i32, BigDecimal)>(conn)?;
+    let cost_bases = cost_bases(conn, portfolio_id, date)?;
 
-    let mut asset_ids = vec![];
-    let mut quantities = vec![];
-
-    for (asset_id, group) in &trades.into_iter().group_by(|(asset_id, _)| *asset_id) {
-        quantities.push(group.map(|(_, quantity)| quantity).sum::<BigDecimal>());
-        asset_ids.push(asset_id);
-    }
+    let asset_ids = cost_bases
+        .iter()
+        .map(|cost_basis| cost_basis.asset_id)
+        .collect::<Vec<_>>();
 
     let prices = latest_prices(conn, &asset_ids, date)?;
     let assetables = retrieve_assetables(conn, &asset_ids)?;
+    let incomes = incomes(conn, portfolio_id, date)?;
+    let rates = treasury_bond_rates(conn, portfolio_id, date)?;
+    let redemptions = redemptions(conn, &asset_ids, date)?;
+    let ledger = cash_ledger(conn, portfolio_id, date)?;
 
     let mut portfolio_amount = BigDecimal::zero();
+    let mut portfolio_income = BigDecimal::zero();
     let mut assets = vec![];
+    let mut settled = vec![];
+
+    for ((cost_basis, price), assetable) in cost_bases.into_iter().zip(prices).zip(assetables) {
+        let amount = &price * &cost_basis.quantity;
 
-    for i in 0..quantities.len() {
-        let price = prices[i].clone();
-        let quantity = quantities[i].clone();
-        let amount = &price * &quantity;
+        // Redemptions give back the principal, which is already in the realized profit
+        // through the sell they are booked as
+        let income = incomes
+            .iter()
+            .filter(|income| income.asset_id == cost_basis.asset_id)
+            .filter(|income| income.kind != IncomeKind::Redemption)
+            .map(|income| income.amount.clone())
+            .sum::<BigDecimal>();
+
+        let (contracted_rate, market_rate) = rates
+            .iter()
+            .find(|rate| rate.asset_id == cost_basis.asset_id)
+            .map_or((None, None), |rate| {
+                (rate.contracted_rate.clone(), rate.market_rate.clone())
+            });
 
         portfolio_amount += &amount;
+        portfolio_income += &income;
+
+        // Redeemed bonds are no longer held, but are kept apart for their realized return
+        let redeemed = cost_basis.quantity.is_zero()
+            && redemptions
+                .iter()
+                .any(|(asset_id, _, _)| *asset_id == cost_basis.asset_id);
 
-        assets.push(AssetPosition {
-            assetable: assetables[i].clone(),
-            quantity,
+        let asset_position = AssetPosition {
+            unrealized_profit: &amount - &cost_basis.cost,
+            realized_profit: cost_basis.realized_profit,
+            average_price: cost_basis.average_price,
+            quantity: cost_basis.quantity,
+            sells: cost_basis.sells,
+            cost: cost_basis.cost,
+            contracted_rate,
+            market_rate,
+            assetable,
+            income,
             amount,
             price,
-        })
+        };
+
+        if redeemed {
+            settled.push(asset_position);
+        } else {
+            assets.push(asset_position);
+        }
     }
 
     Ok(PortfolioPosition {
         amount: portfolio_amount,
+        income: portfolio_income,
+        cash: cash_balance(&ledger, date),
         assets,
+        settled,
     })
 }
@@ -0,0 +1,76 @@
+use crate::schema::{import_runs, price_revisions};
+use bigdecimal::BigDecimal;
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+
+#[derive(Insertable)]
+#[table_name = "price_revisions"]
+struct NewPriceRevision<'a> {
+    asset_id: i32,
+    date: &'a NaiveDate,
+    price: &'a BigDecimal,
+    import_run_id: i32,
+}
+
+// Revisions stored before runs were recorded have no run nor source
+#[derive(Queryable)]
+pub struct PriceRevision {
+    pub date: NaiveDate,
+    pub price: BigDecimal,
+    pub import_run_id: Option<i32>,
+    pub source: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+pub(super) fn register_price_revisions(
+    conn: &PgConnection,
+    asset_id: i32,
+    import_run_id: i32,
+    prices: &[(NaiveDate, BigDecimal)],
+) -> QueryResult<usize> {
+    if prices.is_empty() {
+        return Ok(0);
+    }
+
+    let new_revisions: Vec<_> = prices
+        .iter()
+        .map(|(date, price)| NewPriceRevision {
+            asset_id,
+            date,
+            price,
+            import_run_id,
+        })
+        .collect();
+
+    diesel::insert_into(price_revisions::table)
+        .values(&new_revisions)
+        .execute(conn)
+}
+
+// Every value an asset's prices ever had, oldest first for each date
+pub fn price_revisions(
+    conn: &PgConnection,
+    asset_id: i32,
+    date: Option<NaiveDate>,
+) -> QueryResult<Vec<PriceRevision>> {
+    let mut query = price_revisions::table
+        .left_join(import_runs::table)
+        .select((
+            price_revisions::date,
+            price_revisions::price,
+            price_revisions::import_run_id,
+            import_runs::source.nullable(),
+            price_revisions::created_at,
+        ))
+        .filter(price_revisions::asset_id.eq(asset_id))
+        .into_boxed();
+
+    if let Some(date) = date {
+        query = query.filter(price_revisions::date.eq(date));
+    }
+
+    query
+        .order((price_revisions::date, price_revisions::id))
+        .load(conn)
+}
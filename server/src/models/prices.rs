@@ -1,8 +1,14 @@
-use crate::models::assets::{register_etf_asset, register_treasury_bond_asset};
-use crate::schema::asset_prices;
+use crate::models::assets::{
+    register_crypto_asset, register_etf_asset, register_fii_asset, register_fund_asset,
+    register_stock_asset, register_treasury_bond_asset,
+};
+use crate::models::exchange_rates::{brl_prices, Currency};
+use crate::models::price_revisions::register_price_revisions;
+use crate::models::private_bonds::private_bond_prices;
+use crate::schema::{asset_prices, etfs, treasury_bonds};
 use bigdecimal::{BigDecimal, Zero};
 use chrono::NaiveDate;
-use diesel::dsl::count_star;
+use diesel::pg::upsert::excluded;
 use diesel::pg::PgConnection;
 use diesel::prelude::*;
 
@@ -13,60 +19,173 @@ struct NewAssetPrice {
     price: BigDecimal,
     date: NaiveDate,
 }
-fn replace_asset_prices(
+
+#[derive(Clone, Copy)]
+pub struct PriceCounts {
+    pub inserted: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+}
+
+// Only dates not stored yet are inserted and only prices that changed are updated,
+// so registering a shorter window than the one stored never loses anything.
+// Both are kept as revisions of the run, so the values they replaced can still be found.
+fn upsert_asset_prices(
     conn: &PgConnection,
     asset_id: i32,
-    prices: Vec<(NaiveDate, BigDecimal)>,
-) -> QueryResult<usize> {
-    let prev_prices_count: i64 = asset_prices::table
-        .select(count_star())
+    import_run_id: i32,
+    mut prices: Vec<(NaiveDate, BigDecimal)>,
+) -> QueryResult<PriceCounts> {
+    let mut counts = PriceCounts {
+        inserted: 0,
+        updated: 0,
+        unchanged: 0,
+    };
+
+    // Sources may repeat a date, in which case the last price given wins
+    prices.reverse();
+    prices.sort_by_key(|(date, _)| *date);
+    prices.dedup_by_key(|(date, _)| *date);
+
+    let (first_date, last_date) = match (prices.first(), prices.last()) {
+        (Some((first_date, _)), Some((last_date, _))) => (*first_date, *last_date),
+        _ => return Ok(counts),
+    };
+
+    let prev_prices = asset_prices::table
+        .select((asset_prices::date, asset_prices::price))
         .filter(asset_prices::asset_id.eq(asset_id))
-        .get_result(conn)?;
+        .filter(asset_prices::date.ge(first_date))
+        .filter(asset_prices::date.le(last_date))
+        .order(asset_prices::date)
+        .load::<(NaiveDate, BigDecimal)>(conn)?;
+
+    let mut changed_prices = vec![];
+
+    for (date, price) in prices {
+        match prev_prices.binary_search_by_key(&date, |(prev_date, _)| *prev_date) {
+            Err(_) => counts.inserted += 1,
+            Ok(index) if prev_prices[index].1 != price => counts.updated += 1,
+            Ok(_) => {
+                counts.unchanged += 1;
+                continue;
+            }
+        }
 
-    let new_prices_count = prices.len() as i64 - prev_prices_count;
-    if new_prices_count <= 0 {
-        return Ok(0);
+        changed_prices.push((date, price));
     }
 
-    diesel::delete(asset_prices::table.filter(asset_prices::asset_id.eq(asset_id)))
-        .execute(conn)?;
+    if changed_prices.is_empty() {
+        return Ok(counts);
+    }
 
-    let insertable_prices: Vec<_> = prices
-        .into_iter()
+    let insertable_prices: Vec<_> = changed_prices
+        .iter()
         .map(|(date, price)| NewAssetPrice {
+            price: price.clone(),
+            date: *date,
             asset_id,
-            price,
-            date,
         })
         .collect();
 
     diesel::insert_into(asset_prices::table)
         .values(insertable_prices)
+        .on_conflict((asset_prices::asset_id, asset_prices::date))
+        .do_update()
+        .set(asset_prices::price.eq(excluded(asset_prices::price)))
         .execute(conn)?;
 
-    Ok(new_prices_count as usize)
+    register_price_revisions(conn, asset_id, import_run_id, &changed_prices)?;
+
+    Ok(counts)
 }
 
 pub fn register_treasury_bond_prices(
     conn: &PgConnection,
     key: &'static str,
     maturity_date: NaiveDate,
+    import_run_id: i32,
     prices: Vec<(NaiveDate, BigDecimal)>,
-) -> QueryResult<usize> {
+) -> QueryResult<PriceCounts> {
     conn.transaction(|| {
         let asset_id = register_treasury_bond_asset(conn, key, maturity_date)?;
-        replace_asset_prices(conn, asset_id, prices)
+        upsert_asset_prices(conn, asset_id, import_run_id, prices)
     })
 }
 
+pub fn last_treasury_bond_price_date(conn: &PgConnection) -> QueryResult<Option<NaiveDate>> {
+    asset_prices::table
+        .inner_join(treasury_bonds::table.on(treasury_bonds::id.eq(asset_prices::asset_id)))
+        .select(diesel::dsl::max(asset_prices::date))
+        .first(conn)
+}
+
 pub fn register_etf_prices(
     conn: &PgConnection,
     ticker: &str,
+    import_run_id: i32,
     prices: Vec<(NaiveDate, BigDecimal)>,
-) -> QueryResult<usize> {
+) -> QueryResult<PriceCounts> {
     conn.transaction(|| {
         let asset_id = register_etf_asset(conn, ticker)?;
-        replace_asset_prices(conn, asset_id, prices)
+        upsert_asset_prices(conn, asset_id, import_run_id, prices)
+    })
+}
+
+pub fn last_etf_price_date(conn: &PgConnection, ticker: &str) -> QueryResult<Option<NaiveDate>> {
+    asset_prices::table
+        .inner_join(etfs::table.on(etfs::id.eq(asset_prices::asset_id)))
+        .select(diesel::dsl::max(asset_prices::date))
+        .filter(etfs::ticker.eq(ticker))
+        .first(conn)
+}
+
+pub fn register_stock_prices(
+    conn: &PgConnection,
+    ticker: &str,
+    import_run_id: i32,
+    prices: Vec<(NaiveDate, BigDecimal)>,
+) -> QueryResult<PriceCounts> {
+    conn.transaction(|| {
+        let asset_id = register_stock_asset(conn, ticker)?;
+        upsert_asset_prices(conn, asset_id, import_run_id, prices)
+    })
+}
+
+pub fn register_fii_prices(
+    conn: &PgConnection,
+    ticker: &str,
+    import_run_id: i32,
+    prices: Vec<(NaiveDate, BigDecimal)>,
+) -> QueryResult<PriceCounts> {
+    conn.transaction(|| {
+        let asset_id = register_fii_asset(conn, ticker)?;
+        upsert_asset_prices(conn, asset_id, import_run_id, prices)
+    })
+}
+
+pub fn register_fund_prices(
+    conn: &PgConnection,
+    cnpj: &str,
+    import_run_id: i32,
+    prices: Vec<(NaiveDate, BigDecimal)>,
+) -> QueryResult<PriceCounts> {
+    conn.transaction(|| {
+        let asset_id = register_fund_asset(conn, cnpj)?;
+        upsert_asset_prices(conn, asset_id, import_run_id, prices)
+    })
+}
+
+pub fn register_crypto_prices(
+    conn: &PgConnection,
+    symbol: &str,
+    currency: Currency,
+    import_run_id: i32,
+    prices: Vec<(NaiveDate, BigDecimal)>,
+) -> QueryResult<PriceCounts> {
+    conn.transaction(|| {
+        let asset_id = register_crypto_asset(conn, symbol, currency)?;
+        upsert_asset_prices(conn, asset_id, import_run_id, prices)
     })
 }
 
@@ -76,19 +195,26 @@ pub fn latest_prices(
     until_date: NaiveDate,
 ) -> QueryResult<Vec<BigDecimal>> {
     let prices = asset_prices::table
-        .select((asset_prices::asset_id, asset_prices::price))
+        .select((
+            asset_prices::asset_id,
+            asset_prices::date,
+            asset_prices::price,
+        ))
         .distinct_on(asset_prices::asset_id)
         .filter(asset_prices::date.le(until_date))
         .filter(asset_prices::asset_id.eq_any(asset_ids))
         .order((asset_prices::asset_id, asset_prices::date.desc()))
-        .load::<(i32, BigDecimal)>(conn)?;
+        .load::<(i32, NaiveDate, BigDecimal)>(conn)?;
+
+    let mut prices = brl_prices(conn, prices)?;
+    prices.extend(private_bond_prices(conn, asset_ids, &[until_date])?);
 
     Ok(asset_ids
         .iter()
         .map(|asset_id| {
             prices
                 .iter()
-                .find_map(|(aid, price)| {
+                .find_map(|(aid, _, price)| {
                     if aid == asset_id {
                         Some(price.clone())
                     } else {
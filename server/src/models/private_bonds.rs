@@ -0,0 +1,174 @@
+use crate::models::assets::{register_private_bond_asset, PrivateBond};
+use crate::models::benchmarks::{benchmark_values, monthly_rate_index, Benchmark};
+use crate::schema::private_bonds;
+use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive, Zero};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+
+#[derive(Clone, Copy)]
+pub enum BondKind {
+    Cdb,
+    Lci,
+    Lca,
+    Debenture,
+}
+
+impl BondKind {
+    pub fn from_key(key: &str) -> Option<BondKind> {
+        match key {
+            "CDB" => Some(BondKind::Cdb),
+            "LCI" => Some(BondKind::Lci),
+            "LCA" => Some(BondKind::Lca),
+            "Debenture" => Some(BondKind::Debenture),
+            _ => None,
+        }
+    }
+
+    pub(super) fn key(self) -> &'static str {
+        match self {
+            BondKind::Cdb => "CDB",
+            BondKind::Lci => "LCI",
+            BondKind::Lca => "LCA",
+            BondKind::Debenture => "Debenture",
+        }
+    }
+}
+
+// Pre rates are yearly, CDI rates are a percentage of it and IPCA rates are the yearly spread
+#[derive(Clone, Copy)]
+pub enum BondIndex {
+    Pre,
+    Cdi,
+    Ipca,
+}
+
+impl BondIndex {
+    pub fn from_key(key: &str) -> Option<BondIndex> {
+        match key {
+            "Pre" => Some(BondIndex::Pre),
+            "CDI" => Some(BondIndex::Cdi),
+            "IPCA" => Some(BondIndex::Ipca),
+            _ => None,
+        }
+    }
+
+    pub(super) fn key(self) -> &'static str {
+        match self {
+            BondIndex::Pre => "Pre",
+            BondIndex::Cdi => "CDI",
+            BondIndex::Ipca => "IPCA",
+        }
+    }
+}
+
+pub struct PrivateBondIssue {
+    pub code: String,
+    pub issuer: String,
+    pub bond_kind: BondKind,
+    pub index: BondIndex,
+    pub rate: BigDecimal,
+    pub issue_date: NaiveDate,
+    pub maturity_date: NaiveDate,
+    pub issue_price: BigDecimal,
+}
+
+pub fn register_private_bonds(
+    conn: &PgConnection,
+    issues: &[PrivateBondIssue],
+) -> QueryResult<usize> {
+    conn.transaction(|| {
+        for issue in issues {
+            register_private_bond_asset(conn, issue)?;
+        }
+
+        Ok(issues.len())
+    })
+}
+
+// Private bonds have no market price, so their unit price is accrued from the issue
+// price day by day along their index curve, until it stops at maturity
+pub fn private_bond_prices(
+    conn: &PgConnection,
+    asset_ids: &[i32],
+    dates: &[NaiveDate],
+) -> QueryResult<Vec<(i32, NaiveDate, BigDecimal)>> {
+    let last_date = match dates.iter().max() {
+        Some(last_date) => *last_date,
+        None => return Ok(vec![]),
+    };
+
+    let private_bonds = private_bonds::table
+        .filter(private_bonds::id.eq_any(asset_ids))
+        .load::<PrivateBond>(conn)?;
+
+    if private_bonds.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let cdi_rates = benchmark_values(conn, Benchmark::Cdi, last_date)?;
+    let ipca_rates = benchmark_values(conn, Benchmark::Ipca, last_date)?;
+
+    let mut prices = vec![];
+
+    for private_bond in &private_bonds {
+        let rate = private_bond.rate.to_f64().unwrap_or(0.0);
+        let issue_price = private_bond.issue_price.to_f64().unwrap_or(0.0);
+        let issue_date = private_bond.issue_date;
+
+        for date in dates {
+            let accrual_date = std::cmp::min(*date, private_bond.maturity_date);
+
+            let factor = if accrual_date <= issue_date {
+                1.0
+            } else {
+                match private_bond.index() {
+                    BondIndex::Pre => yearly_factor(rate, business_days(issue_date, accrual_date)),
+                    BondIndex::Cdi => cdi_factor(&cdi_rates, rate, issue_date, accrual_date),
+                    BondIndex::Ipca => {
+                        monthly_rate_index(&ipca_rates, accrual_date)
+                            / monthly_rate_index(&ipca_rates, issue_date)
+                            * yearly_factor(rate, business_days(issue_date, accrual_date))
+                    }
+                }
+            };
+
+            let price = BigDecimal::from_f64(issue_price * factor)
+                .map_or_else(BigDecimal::zero, |price| price.with_scale(8));
+
+            prices.push((private_bond.id, *date, price));
+        }
+    }
+
+    Ok(prices)
+}
+
+// Yearly rates are compounded over the 252 business days convention
+fn yearly_factor(rate: f64, business_days: i64) -> f64 {
+    (1.0 + rate / 100.0).powf(business_days as f64 / 252.0)
+}
+
+// CDI is a percentage per business day, of which the bond earns its rate
+fn cdi_factor(cdi_rates: &[(NaiveDate, f64)], rate: f64, from: NaiveDate, to: NaiveDate) -> f64 {
+    cdi_rates
+        .iter()
+        .skip_while(|(cdi_date, _)| *cdi_date < from)
+        .take_while(|(cdi_date, _)| *cdi_date < to)
+        .map(|(_, cdi_rate)| 1.0 + cdi_rate / 100.0 * rate / 100.0)
+        .product()
+}
+
+// Holidays aren't known, so only weekends are skipped
+pub(super) fn business_days(from: NaiveDate, to: NaiveDate) -> i64 {
+    let mut date = from;
+    let mut days = 0;
+
+    while date < to {
+        if date.weekday() != Weekday::Sat && date.weekday() != Weekday::Sun {
+            days += 1;
+        }
+        date += Duration::days(1);
+    }
+
+    days
+}
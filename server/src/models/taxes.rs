@@ -0,0 +1,366 @@
+use crate::models::assets::{retrieve_assetables, Assetable};
+use crate::models::cost_bases::cost_bases;
+use crate::schema::trades;
+use bigdecimal::{BigDecimal, Zero};
+use chrono::{Datelike, NaiveDate};
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use itertools::Itertools;
+use std::collections::VecDeque;
+
+pub struct MonthlyTax {
+    pub month: NaiveDate,
+    pub swing_trade_sales: BigDecimal,
+    pub stock_sales: BigDecimal,
+    pub stock_exempt_profit: BigDecimal,
+    pub swing_trade_result: BigDecimal,
+    pub swing_trade_losses_used: BigDecimal,
+    pub swing_trade_base: BigDecimal,
+    pub swing_trade_tax: BigDecimal,
+    pub carried_losses: BigDecimal,
+    pub fii_result: BigDecimal,
+    pub fii_losses_used: BigDecimal,
+    pub fii_base: BigDecimal,
+    pub fii_tax: BigDecimal,
+    pub fii_carried_losses: BigDecimal,
+    pub treasury_bond_iof: BigDecimal,
+    pub treasury_bond_ir: BigDecimal,
+    pub fund_iof: BigDecimal,
+    pub fund_ir: BigDecimal,
+    pub private_bond_iof: BigDecimal,
+    pub private_bond_ir: BigDecimal,
+}
+
+enum Sell {
+    Stock {
+        sale: BigDecimal,
+        profit: BigDecimal,
+    },
+    SwingTrade {
+        sale: BigDecimal,
+        profit: BigDecimal,
+    },
+    Fii {
+        profit: BigDecimal,
+    },
+    TreasuryBond {
+        iof: BigDecimal,
+        ir: BigDecimal,
+    },
+    Fund {
+        iof: BigDecimal,
+        ir: BigDecimal,
+    },
+    PrivateBond {
+        iof: BigDecimal,
+        ir: BigDecimal,
+    },
+}
+
+#[derive(Queryable)]
+struct Trade {
+    asset_id: i32,
+    date: NaiveDate,
+    quantity: BigDecimal,
+    price: BigDecimal,
+}
+
+pub fn monthly_taxes(
+    conn: &PgConnection,
+    portfolio_id: i32,
+    until_date: NaiveDate,
+) -> QueryResult<Vec<MonthlyTax>> {
+    let cost_bases = cost_bases(conn, portfolio_id, until_date)?;
+
+    let asset_ids = cost_bases
+        .iter()
+        .map(|cost_basis| cost_basis.asset_id)
+        .collect::<Vec<_>>();
+
+    let assetables = retrieve_assetables(conn, &asset_ids)?;
+
+    let mut sells = vec![];
+    let mut treasury_bond_ids = vec![];
+    let mut fund_ids = vec![];
+    let mut private_bond_ids = vec![];
+
+    for (cost_basis, assetable) in cost_bases.into_iter().zip(assetables) {
+        match assetable {
+            Assetable::Etf(_) => {
+                for sell in cost_basis.sells {
+                    sells.push((
+                        sell.date,
+                        Sell::SwingTrade {
+                            sale: &sell.quantity * &sell.price,
+                            profit: sell.profit,
+                        },
+                    ));
+                }
+            }
+            Assetable::Stock(_) => {
+                for sell in cost_basis.sells {
+                    sells.push((
+                        sell.date,
+                        Sell::Stock {
+                            sale: &sell.quantity * &sell.price,
+                            profit: sell.profit,
+                        },
+                    ));
+                }
+            }
+            Assetable::Fii(_) => {
+                for sell in cost_basis.sells {
+                    sells.push((
+                        sell.date,
+                        Sell::Fii {
+                            profit: sell.profit,
+                        },
+                    ));
+                }
+            }
+            Assetable::TreasuryBond(treasury_bond) => {
+                treasury_bond_ids.push(treasury_bond.id);
+            }
+            Assetable::Fund(fund) => {
+                fund_ids.push(fund.id);
+            }
+            // Crypto gains are reported through GCAP instead, so they are not estimated here
+            Assetable::Crypto(_) => {}
+            Assetable::PrivateBond(private_bond) => {
+                if !private_bond.is_tax_exempt() {
+                    private_bond_ids.push(private_bond.id);
+                }
+            }
+        }
+    }
+
+    let withheld_trades = trades::table
+        .select((
+            trades::asset_id,
+            trades::date,
+            trades::quantity,
+            trades::price,
+        ))
+        .filter(trades::portfolio_id.eq(portfolio_id))
+        .filter(
+            trades::asset_id
+                .eq_any(&treasury_bond_ids)
+                .or(trades::asset_id.eq_any(&fund_ids))
+                .or(trades::asset_id.eq_any(&private_bond_ids)),
+        )
+        .filter(trades::date.le(until_date))
+        .order((trades::asset_id, trades::date, trades::id))
+        .load::<Trade>(conn)?;
+
+    for (asset_id, trades) in &withheld_trades.iter().group_by(|trade| trade.asset_id) {
+        for (date, iof, ir) in withheld_sells(trades) {
+            if fund_ids.contains(&asset_id) {
+                sells.push((date, Sell::Fund { iof, ir }));
+            } else if private_bond_ids.contains(&asset_id) {
+                sells.push((date, Sell::PrivateBond { iof, ir }));
+            } else {
+                sells.push((date, Sell::TreasuryBond { iof, ir }));
+            }
+        }
+    }
+
+    sells.sort_by_key(|(date, _)| *date);
+
+    let mut monthly_taxes = vec![];
+    let mut carried_losses = BigDecimal::zero();
+    let mut fii_carried_losses = BigDecimal::zero();
+
+    for (month, sells) in &sells.into_iter().group_by(|(date, _)| month_of(*date)) {
+        let mut swing_trade_sales = BigDecimal::zero();
+        let mut swing_trade_result = BigDecimal::zero();
+        let mut stock_sales = BigDecimal::zero();
+        let mut stock_result = BigDecimal::zero();
+        let mut fii_result = BigDecimal::zero();
+        let mut treasury_bond_iof = BigDecimal::zero();
+        let mut treasury_bond_ir = BigDecimal::zero();
+        let mut fund_iof = BigDecimal::zero();
+        let mut fund_ir = BigDecimal::zero();
+        let mut private_bond_iof = BigDecimal::zero();
+        let mut private_bond_ir = BigDecimal::zero();
+
+        for (_, sell) in sells {
+            match sell {
+                Sell::Stock { sale, profit } => {
+                    stock_sales += sale;
+                    stock_result += profit;
+                }
+                Sell::SwingTrade { sale, profit } => {
+                    swing_trade_sales += sale;
+                    swing_trade_result += profit;
+                }
+                Sell::Fii { profit } => {
+                    fii_result += profit;
+                }
+                Sell::TreasuryBond { iof, ir } => {
+                    treasury_bond_iof += iof;
+                    treasury_bond_ir += ir;
+                }
+                Sell::Fund { iof, ir } => {
+                    fund_iof += iof;
+                    fund_ir += ir;
+                }
+                Sell::PrivateBond { iof, ir } => {
+                    private_bond_iof += iof;
+                    private_bond_ir += ir;
+                }
+            }
+        }
+
+        swing_trade_sales += &stock_sales;
+
+        // Stock profits are exempt in months with sales up to R$20k, but losses still count
+        let stock_exempt_profit =
+            if stock_sales <= stock_exemption_limit() && stock_result > BigDecimal::zero() {
+                stock_result
+            } else {
+                swing_trade_result += stock_result;
+                BigDecimal::zero()
+            };
+
+        let (swing_trade_losses_used, swing_trade_base) =
+            offset_losses(&swing_trade_result, &mut carried_losses);
+
+        let (fii_losses_used, fii_base) = offset_losses(&fii_result, &mut fii_carried_losses);
+
+        monthly_taxes.push(MonthlyTax {
+            swing_trade_tax: (&swing_trade_base * swing_trade_rate()).with_scale(2),
+            carried_losses: carried_losses.clone(),
+            fii_tax: (&fii_base * fii_rate()).with_scale(2),
+            fii_carried_losses: fii_carried_losses.clone(),
+            treasury_bond_iof: treasury_bond_iof.with_scale(2),
+            treasury_bond_ir: treasury_bond_ir.with_scale(2),
+            fund_iof: fund_iof.with_scale(2),
+            fund_ir: fund_ir.with_scale(2),
+            private_bond_iof: private_bond_iof.with_scale(2),
+            private_bond_ir: private_bond_ir.with_scale(2),
+            swing_trade_losses_used,
+            swing_trade_result,
+            swing_trade_sales,
+            swing_trade_base,
+            stock_exempt_profit,
+            stock_sales,
+            fii_losses_used,
+            fii_result,
+            fii_base,
+            month,
+        });
+    }
+
+    Ok(monthly_taxes)
+}
+
+// Losses are carried forward to be offset against later profits of the same kind,
+// returning the losses used and what is left to be taxed
+fn offset_losses(result: &BigDecimal, carried_losses: &mut BigDecimal) -> (BigDecimal, BigDecimal) {
+    if *result < BigDecimal::zero() {
+        *carried_losses -= result;
+        (BigDecimal::zero(), BigDecimal::zero())
+    } else if *result < *carried_losses {
+        *carried_losses -= result;
+        (result.clone(), BigDecimal::zero())
+    } else {
+        let losses_used = std::mem::replace(carried_losses, BigDecimal::zero());
+        let base = result - &losses_used;
+        (losses_used, base)
+    }
+}
+
+// Treasury bonds, funds and private bonds are taxed at the source, lot by lot in the order they
+// were bought, with rates depending on how long each lot was held
+fn withheld_sells<'a>(
+    trades: impl Iterator<Item = &'a Trade>,
+) -> Vec<(NaiveDate, BigDecimal, BigDecimal)> {
+    let mut lots = VecDeque::<(NaiveDate, BigDecimal, BigDecimal)>::new();
+    let mut sells = vec![];
+
+    for trade in trades {
+        if trade.quantity > BigDecimal::zero() {
+            lots.push_back((trade.date, trade.quantity.clone(), trade.price.clone()));
+            continue;
+        }
+
+        let mut quantity = -&trade.quantity;
+        let mut iof = BigDecimal::zero();
+        let mut ir = BigDecimal::zero();
+
+        while quantity > BigDecimal::zero() {
+            let (lot_date, lot_quantity, lot_price) = match lots.front_mut() {
+                Some(lot) => lot,
+                None => break,
+            };
+
+            let sold_quantity = if *lot_quantity > quantity {
+                quantity.clone()
+            } else {
+                lot_quantity.clone()
+            };
+
+            let days = (trade.date - *lot_date).num_days();
+            let gain = (&trade.price - &*lot_price) * &sold_quantity;
+
+            if gain > BigDecimal::zero() {
+                let lot_iof = &gain * iof_rate(days);
+                ir += (&gain - &lot_iof) * regressive_rate(days);
+                iof += lot_iof;
+            }
+
+            *lot_quantity -= &sold_quantity;
+            quantity -= &sold_quantity;
+
+            if lot_quantity.is_zero() {
+                lots.pop_front();
+            }
+        }
+
+        sells.push((trade.date, iof, ir));
+    }
+
+    sells
+}
+
+fn month_of(date: NaiveDate) -> NaiveDate {
+    NaiveDate::from_ymd(date.year(), date.month(), 1)
+}
+
+fn swing_trade_rate() -> BigDecimal {
+    BigDecimal::from(15) / BigDecimal::from(100)
+}
+
+fn fii_rate() -> BigDecimal {
+    BigDecimal::from(20) / BigDecimal::from(100)
+}
+
+fn stock_exemption_limit() -> BigDecimal {
+    BigDecimal::from(20000)
+}
+
+pub(super) fn regressive_rate(days: i64) -> BigDecimal {
+    let percentage = match days {
+        0..=180 => 225,
+        181..=360 => 200,
+        361..=720 => 175,
+        _ => 150,
+    };
+
+    BigDecimal::from(percentage) / BigDecimal::from(1000)
+}
+
+pub(super) fn iof_rate(days: i64) -> BigDecimal {
+    let percentage = match days {
+        i64::MIN..=0 => IOF_PERCENTAGES[0],
+        1..=29 => IOF_PERCENTAGES[days as usize - 1],
+        _ => 0,
+    };
+
+    BigDecimal::from(percentage) / BigDecimal::from(100)
+}
+
+const IOF_PERCENTAGES: [i32; 29] = [
+    96, 93, 90, 86, 83, 80, 76, 73, 70, 66, 63, 60, 56, 53, 50, 46, 43, 40, 36, 33, 30, 26, 23, 20,
+    16, 13, 10, 6, 3,
+];
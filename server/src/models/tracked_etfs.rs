@@ -0,0 +1,51 @@
+use crate::schema::tracked_etfs;
+use diesel::pg::upsert::excluded;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+
+#[derive(Insertable)]
+#[table_name = "tracked_etfs"]
+struct NewTrackedEtf<'a> {
+    ticker: &'a str,
+    curr_id: &'a str,
+    sml_id: &'a str,
+}
+
+// The identifiers are the ones investing.com uses for the ETF historical data
+#[derive(Queryable)]
+pub struct TrackedEtf {
+    pub ticker: String,
+    pub curr_id: String,
+    pub sml_id: String,
+}
+
+pub fn tracked_etfs(conn: &PgConnection) -> QueryResult<Vec<TrackedEtf>> {
+    tracked_etfs::table
+        .select((
+            tracked_etfs::ticker,
+            tracked_etfs::curr_id,
+            tracked_etfs::sml_id,
+        ))
+        .order(tracked_etfs::ticker)
+        .load(conn)
+}
+
+pub fn register_tracked_etf(conn: &PgConnection, tracked_etf: &TrackedEtf) -> QueryResult<usize> {
+    diesel::insert_into(tracked_etfs::table)
+        .values(&NewTrackedEtf {
+            ticker: &tracked_etf.ticker,
+            curr_id: &tracked_etf.curr_id,
+            sml_id: &tracked_etf.sml_id,
+        })
+        .on_conflict(tracked_etfs::ticker)
+        .do_update()
+        .set((
+            tracked_etfs::curr_id.eq(excluded(tracked_etfs::curr_id)),
+            tracked_etfs::sml_id.eq(excluded(tracked_etfs::sml_id)),
+        ))
+        .execute(conn)
+}
+
+pub fn remove_tracked_etf(conn: &PgConnection, ticker: &str) -> QueryResult<usize> {
+    diesel::delete(tracked_etfs::table.filter(tracked_etfs::ticker.eq(ticker))).execute(conn)
+}
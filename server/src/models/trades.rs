@@ -1,6 +1,9 @@
-use crate::models::assets::{Etf, TreasuryBond};
-use crate::schema::{etfs, portfolios, trades, treasury_bonds};
-use bigdecimal::BigDecimal;
+use crate::models::assets::{
+    register_crypto_asset, Etf, Fii, Fund, PrivateBond, Stock, TreasuryBond,
+};
+use crate::models::exchange_rates::{brl_rate, Currency};
+use crate::schema::{etfs, fiis, funds, portfolios, private_bonds, stocks, trades, treasury_bonds};
+use bigdecimal::{BigDecimal, Zero};
 use chrono::NaiveDate;
 use diesel::pg::PgConnection;
 use diesel::prelude::*;
@@ -21,6 +24,8 @@ struct Trade {
     date: NaiveDate,
     quantity: BigDecimal,
     price: BigDecimal,
+    brokerage_fee: BigDecimal,
+    emoluments: BigDecimal,
 }
 
 #[derive(Insertable)]
@@ -31,6 +36,23 @@ struct NewTrade<'a> {
     date: &'a NaiveDate,
     quantity: &'a BigDecimal,
     price: &'a BigDecimal,
+    brokerage_fee: &'a BigDecimal,
+    emoluments: &'a BigDecimal,
+}
+
+// Fees are paid on top of the trade amount, whether buying or selling
+pub struct TradeFees {
+    pub brokerage: BigDecimal,
+    pub emoluments: BigDecimal,
+}
+
+impl Default for TradeFees {
+    fn default() -> TradeFees {
+        TradeFees {
+            brokerage: BigDecimal::zero(),
+            emoluments: BigDecimal::zero(),
+        }
+    }
 }
 
 pub struct EtfTrade {
@@ -38,6 +60,48 @@ pub struct EtfTrade {
     pub date: NaiveDate,
     pub price: BigDecimal,
     pub quantity: BigDecimal,
+    pub fees: TradeFees,
+}
+
+pub struct StockTrade {
+    pub ticker: String,
+    pub date: NaiveDate,
+    pub price: BigDecimal,
+    pub quantity: BigDecimal,
+    pub fees: TradeFees,
+}
+
+pub struct FiiTrade {
+    pub ticker: String,
+    pub date: NaiveDate,
+    pub price: BigDecimal,
+    pub quantity: BigDecimal,
+    pub fees: TradeFees,
+}
+
+pub struct FundTrade {
+    pub cnpj: String,
+    pub date: NaiveDate,
+    pub price: BigDecimal,
+    pub quantity: BigDecimal,
+    pub fees: TradeFees,
+}
+
+pub struct PrivateBondTrade {
+    pub code: String,
+    pub date: NaiveDate,
+    pub price: BigDecimal,
+    pub quantity: BigDecimal,
+    pub fees: TradeFees,
+}
+
+pub struct CryptoTrade {
+    pub symbol: String,
+    pub currency: Currency,
+    pub date: NaiveDate,
+    pub price: BigDecimal,
+    pub quantity: BigDecimal,
+    pub fees: TradeFees,
 }
 
 pub struct TreasuryBondTrade {
@@ -46,12 +110,33 @@ pub struct TreasuryBondTrade {
     pub date: NaiveDate,
     pub price: BigDecimal,
     pub quantity: BigDecimal,
+    pub fees: TradeFees,
+}
+
+#[derive(Default)]
+pub struct PortfolioTrades {
+    pub etfs: Vec<EtfTrade>,
+    pub stocks: Vec<StockTrade>,
+    pub fiis: Vec<FiiTrade>,
+    pub funds: Vec<FundTrade>,
+    pub private_bonds: Vec<PrivateBondTrade>,
+    pub treasury_bonds: Vec<TreasuryBondTrade>,
+}
+
+impl PortfolioTrades {
+    pub fn is_empty(&self) -> bool {
+        self.etfs.is_empty()
+            && self.stocks.is_empty()
+            && self.fiis.is_empty()
+            && self.funds.is_empty()
+            && self.private_bonds.is_empty()
+            && self.treasury_bonds.is_empty()
+    }
 }
 
 pub fn register_trades(
     conn: &PgConnection,
-    etf_trades: &[EtfTrade],
-    treasury_bond_trades: &[TreasuryBondTrade],
+    portfolio_trades: &PortfolioTrades,
 ) -> QueryResult<i32> {
     conn.transaction(|| {
         let portfolio = diesel::insert_into(portfolios::table)
@@ -60,7 +145,8 @@ pub fn register_trades(
 
         let mut new_trades = vec![];
 
-        for (ticker, trades) in &etf_trades
+        for (ticker, trades) in &portfolio_trades
+            .etfs
             .iter()
             .sorted_by_key(|t| &t.ticker)
             .group_by(|t| &t.ticker)
@@ -76,11 +162,106 @@ pub fn register_trades(
                     quantity: &trade.quantity,
                     price: &trade.price,
                     date: &trade.date,
+                    brokerage_fee: &trade.fees.brokerage,
+                    emoluments: &trade.fees.emoluments,
+                });
+            }
+        }
+
+        for (ticker, trades) in &portfolio_trades
+            .stocks
+            .iter()
+            .sorted_by_key(|t| &t.ticker)
+            .group_by(|t| &t.ticker)
+        {
+            let stock = stocks::table
+                .filter(stocks::ticker.eq(ticker))
+                .first::<Stock>(conn)?;
+
+            for trade in trades {
+                new_trades.push(NewTrade {
+                    portfolio_id: portfolio.id,
+                    asset_id: stock.id,
+                    quantity: &trade.quantity,
+                    price: &trade.price,
+                    date: &trade.date,
+                    brokerage_fee: &trade.fees.brokerage,
+                    emoluments: &trade.fees.emoluments,
+                });
+            }
+        }
+
+        for (ticker, trades) in &portfolio_trades
+            .fiis
+            .iter()
+            .sorted_by_key(|t| &t.ticker)
+            .group_by(|t| &t.ticker)
+        {
+            let fii = fiis::table
+                .filter(fiis::ticker.eq(ticker))
+                .first::<Fii>(conn)?;
+
+            for trade in trades {
+                new_trades.push(NewTrade {
+                    portfolio_id: portfolio.id,
+                    asset_id: fii.id,
+                    quantity: &trade.quantity,
+                    price: &trade.price,
+                    date: &trade.date,
+                    brokerage_fee: &trade.fees.brokerage,
+                    emoluments: &trade.fees.emoluments,
+                });
+            }
+        }
+
+        for (cnpj, trades) in &portfolio_trades
+            .funds
+            .iter()
+            .sorted_by_key(|t| &t.cnpj)
+            .group_by(|t| &t.cnpj)
+        {
+            let fund = funds::table
+                .filter(funds::cnpj.eq(cnpj))
+                .first::<Fund>(conn)?;
+
+            for trade in trades {
+                new_trades.push(NewTrade {
+                    portfolio_id: portfolio.id,
+                    asset_id: fund.id,
+                    quantity: &trade.quantity,
+                    price: &trade.price,
+                    date: &trade.date,
+                    brokerage_fee: &trade.fees.brokerage,
+                    emoluments: &trade.fees.emoluments,
+                });
+            }
+        }
+
+        for (code, trades) in &portfolio_trades
+            .private_bonds
+            .iter()
+            .sorted_by_key(|t| &t.code)
+            .group_by(|t| &t.code)
+        {
+            let private_bond = private_bonds::table
+                .filter(private_bonds::code.eq(code))
+                .first::<PrivateBond>(conn)?;
+
+            for trade in trades {
+                new_trades.push(NewTrade {
+                    portfolio_id: portfolio.id,
+                    asset_id: private_bond.id,
+                    quantity: &trade.quantity,
+                    price: &trade.price,
+                    date: &trade.date,
+                    brokerage_fee: &trade.fees.brokerage,
+                    emoluments: &trade.fees.emoluments,
                 });
             }
         }
 
-        for ((key, maturity_date), trades) in &treasury_bond_trades
+        for ((key, maturity_date), trades) in &portfolio_trades
+            .treasury_bonds
             .iter()
             .sorted_by_key(|t| (&t.key, t.maturity))
             .group_by(|t| (&t.key, t.maturity))
@@ -97,6 +278,8 @@ pub fn register_trades(
                     quantity: &trade.quantity,
                     price: &trade.price,
                     date: &trade.date,
+                    brokerage_fee: &trade.fees.brokerage,
+                    emoluments: &trade.fees.emoluments,
                 });
             }
         }
@@ -108,3 +291,39 @@ pub fn register_trades(
         Ok(portfolio.id)
     })
 }
+
+// Exchanges quote in foreign currencies too, but trades are kept in reais at their date
+pub fn register_crypto_trades(
+    conn: &PgConnection,
+    portfolio_id: i32,
+    crypto_trades: &[CryptoTrade],
+) -> QueryResult<usize> {
+    conn.transaction(|| {
+        let mut prices = vec![];
+
+        for trade in crypto_trades {
+            let rate = brl_rate(conn, trade.currency, trade.date)?;
+            prices.push(&trade.price * &rate);
+        }
+
+        let mut new_trades = vec![];
+
+        for (trade, price) in crypto_trades.iter().zip(&prices) {
+            let asset_id = register_crypto_asset(conn, &trade.symbol, trade.currency)?;
+
+            new_trades.push(NewTrade {
+                quantity: &trade.quantity,
+                date: &trade.date,
+                brokerage_fee: &trade.fees.brokerage,
+                emoluments: &trade.fees.emoluments,
+                portfolio_id,
+                asset_id,
+                price,
+            });
+        }
+
+        diesel::insert_into(trades::table)
+            .values(&new_trades)
+            .execute(conn)
+    })
+}
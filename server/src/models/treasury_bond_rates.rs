@@ -0,0 +1,176 @@
+use crate::models::assets::register_treasury_bond_asset;
+use crate::models::private_bonds::business_days;
+use crate::schema::{asset_prices, trades, treasury_bond_rates, treasury_bonds};
+use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive};
+use chrono::NaiveDate;
+use diesel::pg::upsert::excluded;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+
+#[derive(Insertable)]
+#[table_name = "treasury_bond_rates"]
+struct NewTreasuryBondRate<'a> {
+    asset_id: i32,
+    date: &'a NaiveDate,
+    buy_rate: Option<&'a BigDecimal>,
+    sell_rate: &'a BigDecimal,
+}
+
+pub struct TreasuryBondRate {
+    pub asset_id: i32,
+    pub contracted_rate: Option<BigDecimal>,
+    pub market_rate: Option<BigDecimal>,
+}
+
+// Rates are yearly percentages, and the buy rate is missing when the bond isn't on sale
+pub fn register_treasury_bond_rates(
+    conn: &PgConnection,
+    key: &'static str,
+    maturity_date: NaiveDate,
+    rates: Vec<(NaiveDate, Option<BigDecimal>, BigDecimal)>,
+) -> QueryResult<usize> {
+    conn.transaction(|| {
+        let asset_id = register_treasury_bond_asset(conn, key, maturity_date)?;
+
+        let insertable_rates: Vec<_> = rates
+            .iter()
+            .map(|(date, buy_rate, sell_rate)| NewTreasuryBondRate {
+                buy_rate: buy_rate.as_ref(),
+                asset_id,
+                sell_rate,
+                date,
+            })
+            .collect();
+
+        diesel::insert_into(treasury_bond_rates::table)
+            .values(&insertable_rates)
+            .on_conflict((treasury_bond_rates::asset_id, treasury_bond_rates::date))
+            .do_update()
+            .set((
+                treasury_bond_rates::buy_rate.eq(excluded(treasury_bond_rates::buy_rate)),
+                treasury_bond_rates::sell_rate.eq(excluded(treasury_bond_rates::sell_rate)),
+            ))
+            .execute(conn)
+    })
+}
+
+// The contracted rate of each purchase is found by moving the market rate of its date
+// by how far the price paid was from the market price. Coupons are not accounted for,
+// which is close enough given prices paid are always near the market ones.
+pub fn treasury_bond_rates(
+    conn: &PgConnection,
+    portfolio_id: i32,
+    date: NaiveDate,
+) -> QueryResult<Vec<TreasuryBondRate>> {
+    let purchases = trades::table
+        .inner_join(treasury_bonds::table.on(treasury_bonds::id.eq(trades::asset_id)))
+        .select((
+            trades::asset_id,
+            trades::date,
+            trades::quantity,
+            trades::price,
+            treasury_bonds::maturity_date,
+        ))
+        .filter(trades::portfolio_id.eq(portfolio_id))
+        .filter(trades::date.le(date))
+        .filter(trades::quantity.gt(BigDecimal::from(0)))
+        .order((trades::asset_id, trades::date))
+        .load::<(i32, NaiveDate, BigDecimal, BigDecimal, NaiveDate)>(conn)?;
+
+    let mut asset_ids = purchases
+        .iter()
+        .map(|(asset_id, _, _, _, _)| *asset_id)
+        .collect::<Vec<_>>();
+
+    asset_ids.dedup();
+
+    let market_rates = treasury_bond_rates::table
+        .select((
+            treasury_bond_rates::asset_id,
+            treasury_bond_rates::date,
+            treasury_bond_rates::sell_rate,
+        ))
+        .filter(treasury_bond_rates::asset_id.eq_any(&asset_ids))
+        .filter(treasury_bond_rates::date.le(date))
+        .order((treasury_bond_rates::asset_id, treasury_bond_rates::date))
+        .load::<(i32, NaiveDate, BigDecimal)>(conn)?;
+
+    let market_prices = asset_prices::table
+        .select((
+            asset_prices::asset_id,
+            asset_prices::date,
+            asset_prices::price,
+        ))
+        .filter(asset_prices::asset_id.eq_any(&asset_ids))
+        .filter(asset_prices::date.le(date))
+        .load::<(i32, NaiveDate, BigDecimal)>(conn)?;
+
+    Ok(asset_ids
+        .into_iter()
+        .map(|asset_id| {
+            let mut weighted_rate = 0.0;
+            let mut total_quantity = 0.0;
+
+            for (_, trade_date, quantity, price, maturity_date) in purchases
+                .iter()
+                .filter(|(trade_asset_id, _, _, _, _)| *trade_asset_id == asset_id)
+            {
+                let market_rate = market_rates.iter().find(|(rate_asset_id, rate_date, _)| {
+                    *rate_asset_id == asset_id && rate_date == trade_date
+                });
+
+                let market_price = market_prices
+                    .iter()
+                    .find(|(price_asset_id, price_date, _)| {
+                        *price_asset_id == asset_id && price_date == trade_date
+                    });
+
+                let days = business_days(*trade_date, *maturity_date);
+
+                if let (Some((_, _, market_rate)), Some((_, _, market_price))) =
+                    (market_rate, market_price)
+                {
+                    if let Some(rate) = contracted_rate(market_rate, market_price, price, days) {
+                        let quantity = quantity.to_f64().unwrap_or(0.0);
+                        weighted_rate += rate * quantity;
+                        total_quantity += quantity;
+                    }
+                }
+            }
+
+            let contracted_rate = if total_quantity > 0.0 {
+                BigDecimal::from_f64(weighted_rate / total_quantity).map(|rate| rate.with_scale(4))
+            } else {
+                None
+            };
+
+            let market_rate = market_rates
+                .iter()
+                .filter(|(rate_asset_id, _, _)| *rate_asset_id == asset_id)
+                .map(|(_, _, rate)| rate.clone())
+                .next_back();
+
+            TreasuryBondRate {
+                asset_id,
+                contracted_rate,
+                market_rate,
+            }
+        })
+        .collect())
+}
+
+fn contracted_rate(
+    market_rate: &BigDecimal,
+    market_price: &BigDecimal,
+    price: &BigDecimal,
+    business_days: i64,
+) -> Option<f64> {
+    if business_days <= 0 {
+        return None;
+    }
+
+    let market_rate = market_rate.to_f64()? / 100.0;
+    let ratio = market_price.to_f64()? / price.to_f64()?;
+
+    Some(((1.0 + market_rate) * ratio.powf(252.0 / business_days as f64) - 1.0) * 100.0)
+}
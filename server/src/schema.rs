@@ -1,3 +1,14 @@
+table! {
+    asset_cash_events (id) {
+        id -> Int4,
+        asset_id -> Int4,
+        kind -> Text,
+        date -> Date,
+        payment_date -> Date,
+        value -> Numeric,
+    }
+}
+
 table! {
     asset_prices (id) {
         id -> Int4,
@@ -14,6 +25,45 @@ table! {
     }
 }
 
+table! {
+    benchmarks (id) {
+        id -> Int4,
+        key -> Text,
+        date -> Date,
+        value -> Numeric,
+    }
+}
+
+table! {
+    cash_movements (id) {
+        id -> Int4,
+        portfolio_id -> Int4,
+        date -> Date,
+        kind -> Text,
+        amount -> Numeric,
+    }
+}
+
+table! {
+    corporate_actions (id) {
+        id -> Int4,
+        asset_id -> Int4,
+        kind -> Text,
+        date -> Date,
+        ratio -> Numeric,
+        new_asset_id -> Nullable<Int4>,
+    }
+}
+
+table! {
+    cryptos (id) {
+        id -> Int4,
+        kind -> Text,
+        symbol -> Text,
+        currency -> Text,
+    }
+}
+
 table! {
     etfs (id) {
         id -> Int4,
@@ -22,12 +72,103 @@ table! {
     }
 }
 
+table! {
+    exchange_rates (id) {
+        id -> Int4,
+        currency -> Text,
+        date -> Date,
+        rate -> Numeric,
+    }
+}
+
+table! {
+    fiis (id) {
+        id -> Int4,
+        kind -> Text,
+        ticker -> Text,
+    }
+}
+
+table! {
+    funds (id) {
+        id -> Int4,
+        kind -> Text,
+        cnpj -> Text,
+    }
+}
+
+table! {
+    import_run_assets (id) {
+        id -> Int4,
+        import_run_id -> Int4,
+        asset -> Text,
+        inserted -> Int4,
+        updated -> Int4,
+        unchanged -> Int4,
+        error -> Nullable<Text>,
+    }
+}
+
+table! {
+    import_runs (id) {
+        id -> Int4,
+        source -> Text,
+        started_at -> Timestamp,
+        finished_at -> Nullable<Timestamp>,
+        outcome -> Nullable<Text>,
+        error -> Nullable<Text>,
+    }
+}
+
 table! {
     portfolios (id) {
         id -> Int4,
     }
 }
 
+table! {
+    price_revisions (id) {
+        id -> Int4,
+        asset_id -> Int4,
+        date -> Date,
+        price -> Numeric,
+        import_run_id -> Nullable<Int4>,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    private_bonds (id) {
+        id -> Int4,
+        kind -> Text,
+        code -> Text,
+        issuer -> Text,
+        bond_kind -> Text,
+        index -> Text,
+        rate -> Numeric,
+        issue_date -> Date,
+        maturity_date -> Date,
+        issue_price -> Numeric,
+    }
+}
+
+table! {
+    stocks (id) {
+        id -> Int4,
+        kind -> Text,
+        ticker -> Text,
+    }
+}
+
+table! {
+    tracked_etfs (id) {
+        id -> Int4,
+        ticker -> Text,
+        curr_id -> Text,
+        sml_id -> Text,
+    }
+}
+
 table! {
     trades (id) {
         id -> Int4,
@@ -36,6 +177,18 @@ table! {
         date -> Date,
         quantity -> Numeric,
         price -> Numeric,
+        brokerage_fee -> Numeric,
+        emoluments -> Numeric,
+    }
+}
+
+table! {
+    treasury_bond_rates (id) {
+        id -> Int4,
+        asset_id -> Int4,
+        date -> Date,
+        buy_rate -> Nullable<Numeric>,
+        sell_rate -> Numeric,
     }
 }
 
@@ -48,15 +201,36 @@ table! {
     }
 }
 
+joinable!(asset_cash_events -> assets (asset_id));
 joinable!(asset_prices -> assets (asset_id));
+joinable!(cash_movements -> portfolios (portfolio_id));
+joinable!(import_run_assets -> import_runs (import_run_id));
+joinable!(price_revisions -> assets (asset_id));
+joinable!(price_revisions -> import_runs (import_run_id));
 joinable!(trades -> assets (asset_id));
 joinable!(trades -> portfolios (portfolio_id));
+joinable!(treasury_bond_rates -> treasury_bonds (asset_id));
 
 allow_tables_to_appear_in_same_query!(
+    asset_cash_events,
     asset_prices,
     assets,
+    benchmarks,
+    cash_movements,
+    corporate_actions,
+    cryptos,
     etfs,
+    exchange_rates,
+    fiis,
+    funds,
+    import_run_assets,
+    import_runs,
     portfolios,
+    price_revisions,
+    private_bonds,
+    stocks,
+    tracked_etfs,
     trades,
+    treasury_bond_rates,
     treasury_bonds,
 );
@@ -1,5 +1,25 @@
+pub mod export_darf;
+pub mod get_annual_declaration;
+pub mod get_import_runs;
+pub mod get_monthly_income;
 pub mod get_portfolio_amounts;
+pub mod get_portfolio_benchmark;
+pub mod get_portfolio_returns;
+pub mod get_portfolio_twr;
+pub mod get_price_revisions;
 pub mod get_transactions;
+pub mod import_benchmarks;
+pub mod import_cash_movements;
+pub mod import_corporate_actions;
+pub mod import_cotahist;
+pub mod import_crypto_trades;
 pub mod import_etfs_prices;
+pub mod import_exchange_rates;
+pub mod import_fii_distributions;
+pub mod import_funds_prices;
+pub mod import_income_events;
+pub mod import_investing_prices;
+pub mod import_private_bonds;
 pub mod import_trades;
 pub mod import_treasury_bonds_prices;
+pub mod price_sources;
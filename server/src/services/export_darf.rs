@@ -0,0 +1,151 @@
+use crate::models::monthly_taxes;
+use bigdecimal::{BigDecimal, Zero};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use diesel::prelude::*;
+
+pub struct Darf {
+    pub month: NaiveDate,
+    pub revenue_code: &'static str,
+    pub calculation_base: BigDecimal,
+    pub losses_used: BigDecimal,
+    pub previous_tax: BigDecimal,
+    pub tax_due: BigDecimal,
+    pub due_date: NaiveDate,
+}
+
+impl Darf {
+    pub fn payable(&self) -> bool {
+        self.tax_due >= minimum_payable_tax()
+    }
+
+    pub fn to_csv(&self) -> String {
+        let header = [
+            "Código da Receita",
+            "Período de Apuração",
+            "Base de Cálculo",
+            "Prejuízo Compensado",
+            "Imposto de Meses Anteriores",
+            "Valor do Imposto",
+            "Data de Vencimento",
+        ];
+
+        format!(
+            "{}\n{};{};{};{};{};{};{}\n",
+            header.join(";"),
+            self.revenue_code,
+            self.month.format("%m/%Y"),
+            brl(&self.calculation_base),
+            brl(&self.losses_used),
+            brl(&self.previous_tax),
+            brl(&self.tax_due),
+            self.due_date.format("%d/%m/%Y"),
+        )
+    }
+
+    pub fn to_html(&self) -> String {
+        let note = if self.payable() {
+            ""
+        } else {
+            "<p>Imposto abaixo de R$ 10,00, deve ser somado ao do próximo mês.</p>"
+        };
+
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="pt-BR">
+<head><meta charset="utf-8"><title>DARF {period}</title></head>
+<body>
+<h1>DARF {period}</h1>
+<table>
+<tr><th>Código da Receita</th><td>{revenue_code}</td></tr>
+<tr><th>Período de Apuração</th><td>{period}</td></tr>
+<tr><th>Base de Cálculo</th><td>R$ {calculation_base}</td></tr>
+<tr><th>Prejuízo Compensado</th><td>R$ {losses_used}</td></tr>
+<tr><th>Imposto de Meses Anteriores</th><td>R$ {previous_tax}</td></tr>
+<tr><th>Valor do Imposto</th><td>R$ {tax_due}</td></tr>
+<tr><th>Data de Vencimento</th><td>{due_date}</td></tr>
+</table>
+{note}
+</body>
+</html>
+"#,
+            period = self.month.format("%m/%Y"),
+            revenue_code = self.revenue_code,
+            calculation_base = brl(&self.calculation_base),
+            losses_used = brl(&self.losses_used),
+            previous_tax = brl(&self.previous_tax),
+            tax_due = brl(&self.tax_due),
+            due_date = self.due_date.format("%d/%m/%Y"),
+            note = note,
+        )
+    }
+}
+
+// Months too far ahead for their due date to be a date have no DARF
+pub fn run(conn: &PgConnection, portfolio_id: i32, month: NaiveDate) -> QueryResult<Option<Darf>> {
+    let month = NaiveDate::from_ymd(month.year(), month.month(), 1);
+
+    let (next_month, due_month) = match first_day_of_next_month(month).and_then(|next_month| {
+        first_day_of_next_month(next_month).map(|due_month| (next_month, due_month))
+    }) {
+        Some(months) => months,
+        None => return Ok(None),
+    };
+
+    let taxes = monthly_taxes(conn, portfolio_id, next_month.pred())?;
+
+    let mut previous_tax = BigDecimal::zero();
+    for tax in taxes.iter().filter(|tax| tax.month < month) {
+        previous_tax += &tax.swing_trade_tax + &tax.fii_tax;
+        if previous_tax >= minimum_payable_tax() {
+            previous_tax = BigDecimal::zero();
+        }
+    }
+
+    let (calculation_base, losses_used, tax) =
+        taxes.into_iter().find(|tax| tax.month == month).map_or(
+            (BigDecimal::zero(), BigDecimal::zero(), BigDecimal::zero()),
+            |tax| {
+                (
+                    &tax.swing_trade_base + &tax.fii_base,
+                    &tax.swing_trade_losses_used + &tax.fii_losses_used,
+                    &tax.swing_trade_tax + &tax.fii_tax,
+                )
+            },
+        );
+
+    Ok(Some(Darf {
+        due_date: last_business_day(due_month.pred()),
+        tax_due: &tax + &previous_tax,
+        revenue_code: SWING_TRADE_REVENUE_CODE,
+        calculation_base,
+        previous_tax,
+        losses_used,
+        month,
+    }))
+}
+
+fn first_day_of_next_month(month: NaiveDate) -> Option<NaiveDate> {
+    if month.month() == 12 {
+        NaiveDate::from_ymd_opt(month.year() + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(month.year(), month.month() + 1, 1)
+    }
+}
+
+// Holidays aren't known, so only weekends are skipped
+fn last_business_day(mut date: NaiveDate) -> NaiveDate {
+    while date.weekday() == Weekday::Sat || date.weekday() == Weekday::Sun {
+        date -= Duration::days(1);
+    }
+    date
+}
+
+fn brl(value: &BigDecimal) -> String {
+    value.with_scale(2).to_string().replace(".", ",")
+}
+
+fn minimum_payable_tax() -> BigDecimal {
+    BigDecimal::from(10)
+}
+
+const SWING_TRADE_REVENUE_CODE: &str = "6015";
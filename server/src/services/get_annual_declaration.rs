@@ -0,0 +1,75 @@
+use crate::models::{position, Assetable};
+use bigdecimal::{BigDecimal, Zero};
+use chrono::NaiveDate;
+use diesel::prelude::*;
+
+pub struct DeclarationItem {
+    pub assetable: Assetable,
+    pub group: &'static str,
+    pub code: &'static str,
+    pub quantity: BigDecimal,
+    pub cost: BigDecimal,
+    pub previous_quantity: BigDecimal,
+    pub previous_cost: BigDecimal,
+}
+
+// Positions are compared at the end of the declared year and at the end of the one before it
+pub fn run(
+    conn: &PgConnection,
+    portfolio_id: i32,
+    year_end: NaiveDate,
+    previous_year_end: NaiveDate,
+) -> QueryResult<Vec<DeclarationItem>> {
+    let current_position = position(conn, portfolio_id, year_end)?;
+    let previous_position = position(conn, portfolio_id, previous_year_end)?;
+
+    let mut items = vec![];
+
+    // Bonds redeemed during the year still have to be declared as no longer held
+    for asset in current_position
+        .assets
+        .into_iter()
+        .chain(current_position.settled)
+    {
+        let (previous_quantity, previous_cost) = previous_position
+            .assets
+            .iter()
+            .find(|previous_asset| previous_asset.assetable.id() == asset.assetable.id())
+            .map_or((BigDecimal::zero(), BigDecimal::zero()), |previous_asset| {
+                (previous_asset.quantity.clone(), previous_asset.cost.clone())
+            });
+
+        if asset.quantity.is_zero() && previous_quantity.is_zero() {
+            continue;
+        }
+
+        let (group, code) = declaration_code(&asset.assetable);
+
+        items.push(DeclarationItem {
+            assetable: asset.assetable,
+            quantity: asset.quantity,
+            cost: asset.cost,
+            previous_quantity,
+            previous_cost,
+            group,
+            code,
+        });
+    }
+
+    Ok(items)
+}
+
+// Suggested group and code from the "Bens e Direitos" section of IRPF
+fn declaration_code(assetable: &Assetable) -> (&'static str, &'static str) {
+    match assetable {
+        Assetable::TreasuryBond(_) => ("04", "02"),
+        Assetable::Etf(_) => ("07", "09"),
+        Assetable::Stock(_) => ("03", "01"),
+        Assetable::Fii(_) => ("07", "03"),
+        Assetable::Fund(_) => ("07", "99"),
+        Assetable::Crypto(crypto) if crypto.symbol == "BTC" => ("08", "01"),
+        Assetable::Crypto(_) => ("08", "02"),
+        Assetable::PrivateBond(private_bond) if private_bond.is_tax_exempt() => ("04", "03"),
+        Assetable::PrivateBond(_) => ("04", "02"),
+    }
+}
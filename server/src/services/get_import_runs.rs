@@ -0,0 +1,6 @@
+use crate::models::{import_runs, ImportRun};
+use diesel::prelude::*;
+
+pub fn run(conn: &PgConnection, source: Option<&str>, limit: i64) -> QueryResult<Vec<ImportRun>> {
+    import_runs(conn, source, limit)
+}
@@ -0,0 +1,60 @@
+use crate::models::{incomes, IncomeKind};
+use bigdecimal::{BigDecimal, Zero};
+use chrono::{Datelike, NaiveDate};
+use diesel::prelude::*;
+use itertools::Itertools;
+
+pub struct MonthlyIncome {
+    pub month: NaiveDate,
+    pub dividends: BigDecimal,
+    pub jcp: BigDecimal,
+    pub distributions: BigDecimal,
+    pub coupons: BigDecimal,
+    pub withheld: BigDecimal,
+    pub total: BigDecimal,
+}
+
+// Amounts are what was received in the month, redemptions are left out as they
+// give back the principal instead of paying income over it
+pub fn run(
+    conn: &PgConnection,
+    portfolio_id: i32,
+    today: NaiveDate,
+) -> QueryResult<Vec<MonthlyIncome>> {
+    let incomes = incomes(conn, portfolio_id, today)?;
+
+    let mut monthly_incomes = vec![];
+
+    for (month, incomes) in &incomes
+        .iter()
+        .filter(|income| income.kind != IncomeKind::Redemption)
+        .group_by(|income| NaiveDate::from_ymd(income.date.year(), income.date.month(), 1))
+    {
+        let mut monthly_income = MonthlyIncome {
+            month,
+            dividends: BigDecimal::zero(),
+            jcp: BigDecimal::zero(),
+            distributions: BigDecimal::zero(),
+            coupons: BigDecimal::zero(),
+            withheld: BigDecimal::zero(),
+            total: BigDecimal::zero(),
+        };
+
+        for income in incomes {
+            match income.kind {
+                IncomeKind::Dividend => monthly_income.dividends += &income.amount,
+                IncomeKind::Jcp => monthly_income.jcp += &income.amount,
+                IncomeKind::Distribution => monthly_income.distributions += &income.amount,
+                IncomeKind::Coupon => monthly_income.coupons += &income.amount,
+                IncomeKind::Redemption => continue,
+            }
+
+            monthly_income.withheld += &income.withheld;
+            monthly_income.total += &income.amount;
+        }
+
+        monthly_incomes.push(monthly_income);
+    }
+
+    Ok(monthly_incomes)
+}
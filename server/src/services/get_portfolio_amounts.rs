@@ -1,3 +1,7 @@
+use crate::models::{
+    adjustment, brl_prices, cash_balance, cash_ledger, corporate_actions, incomes,
+    net_contributions, private_bond_prices, redemptions, successor_asset_ids,
+};
 use crate::schema::{asset_prices, trades};
 use bigdecimal::{BigDecimal, Zero};
 use chrono::{Duration, NaiveDate};
@@ -6,6 +10,9 @@ use diesel::{dsl::sql, prelude::*, sql_types::Numeric};
 pub struct PortfolioAmount {
     pub gross_total: BigDecimal,
     pub invested: BigDecimal,
+    pub income: BigDecimal,
+    pub cash: BigDecimal,
+    pub contributions: BigDecimal,
     pub date: NaiveDate,
 }
 
@@ -14,11 +21,11 @@ pub fn run(
     portfolio_id: i32,
     today: NaiveDate,
 ) -> QueryResult<Vec<PortfolioAmount>> {
-    let trades = trades::table
+    let mut trades = trades::table
         .select((
             trades::asset_id,
             trades::date,
-            sql::<Numeric>("sum(quantity * price)"),
+            sql::<Numeric>("sum(quantity * price + brokerage_fee + emoluments)"),
             sql::<Numeric>("sum(quantity)"),
         ))
         .filter(trades::portfolio_id.eq(portfolio_id))
@@ -31,6 +38,23 @@ pub fn run(
         None => return Ok(vec![]),
     };
 
+    let actions = corporate_actions(conn, today)?;
+
+    let price_asset_ids = successor_asset_ids(
+        &actions,
+        &trades
+            .iter()
+            .map(|trade| trade.asset_id)
+            .collect::<Vec<_>>(),
+    );
+
+    // Trades are restated as the asset they became after splits, renames and mergers
+    for trade in &mut trades {
+        let (asset_id, ratio) = adjustment(&actions, trade.asset_id, trade.date, today);
+        trade.asset_id = asset_id;
+        trade.quantity = &trade.quantity * ratio;
+    }
+
     let mut asset_ids = trades
         .iter()
         .map(|trade| trade.asset_id)
@@ -45,10 +69,44 @@ pub fn run(
             asset_prices::date,
             asset_prices::price,
         ))
-        .filter(asset_prices::asset_id.eq_any(&asset_ids))
+        .filter(asset_prices::asset_id.eq_any(&price_asset_ids))
         .filter(asset_prices::date.eq_any(&dates))
         .order((asset_prices::asset_id, asset_prices::date.desc()))
-        .load::<Price>(conn)?;
+        .load::<(i32, NaiveDate, BigDecimal)>(conn)?;
+
+    let mut prices = brl_prices(conn, prices)?;
+    prices.extend(private_bond_prices(conn, &asset_ids, &dates)?);
+
+    // Prices from before a corporate action are adjusted to the asset it became, so
+    // the series stays continuous, but prices the asset already had are kept
+    let mut prices = prices
+        .into_iter()
+        .map(|(price_asset_id, date, price)| {
+            let (asset_id, ratio) = adjustment(&actions, price_asset_id, date, today);
+
+            let price = Price {
+                price: price / ratio,
+                asset_id,
+                date,
+            };
+
+            (price_asset_id != asset_id, price)
+        })
+        .collect::<Vec<_>>();
+
+    prices.sort_by(|(a_adjusted, a), (b_adjusted, b)| {
+        (a.asset_id, b.date, a_adjusted).cmp(&(b.asset_id, a.date, b_adjusted))
+    });
+    prices.dedup_by_key(|(_, price)| (price.asset_id, price.date));
+
+    let prices = prices
+        .into_iter()
+        .map(|(_, price)| price)
+        .collect::<Vec<_>>();
+
+    let incomes = incomes(conn, portfolio_id, today)?;
+    let redemptions = redemptions(conn, &asset_ids, today)?;
+    let ledger = cash_ledger(conn, portfolio_id, today)?;
 
     let mut portfolio_amounts = vec![];
 
@@ -58,6 +116,13 @@ pub fn run(
         let mut invested = BigDecimal::zero();
 
         for asset_id in &asset_ids {
+            // Once redeemed, the bond is worth nothing and its value shows up as income
+            let redeemed = redemptions
+                .iter()
+                .any(|(redeemed_asset_id, payment_date, _)| {
+                    redeemed_asset_id == asset_id && *payment_date <= date
+                });
+
             let price = prices
                 .iter()
                 .filter(|price| price.asset_id == *asset_id)
@@ -66,6 +131,8 @@ pub fn run(
                 .next()
                 .unwrap_or(&zero);
 
+            let price = if redeemed { &zero } else { price };
+
             for date_trade in trades
                 .iter()
                 .filter(|trade| trade.date <= date && trade.asset_id == *asset_id)
@@ -75,9 +142,18 @@ pub fn run(
             }
         }
 
+        let income = incomes
+            .iter()
+            .filter(|income| income.date <= date)
+            .map(|income| income.amount.clone())
+            .sum();
+
         portfolio_amounts.push(PortfolioAmount {
+            cash: cash_balance(&ledger, date),
+            contributions: net_contributions(&ledger, date),
             gross_total,
             invested,
+            income,
             date,
         });
     }
@@ -108,7 +184,6 @@ struct Trade {
     quantity: BigDecimal,
 }
 
-#[derive(Queryable)]
 struct Price {
     asset_id: i32,
     date: NaiveDate,
@@ -0,0 +1,49 @@
+use crate::{
+    models::{benchmark_indexes, Benchmark},
+    services::get_portfolio_twr,
+};
+use chrono::NaiveDate;
+use diesel::prelude::*;
+
+pub struct BenchmarkComparison {
+    pub date: NaiveDate,
+    pub portfolio: f64,
+    pub benchmark: Option<f64>,
+}
+
+pub fn run(
+    conn: &PgConnection,
+    portfolio_id: i32,
+    benchmark: Benchmark,
+    today: NaiveDate,
+) -> QueryResult<Vec<BenchmarkComparison>> {
+    let twr_indexes = get_portfolio_twr::run(conn, portfolio_id, today)?.indexes;
+
+    let dates = twr_indexes
+        .iter()
+        .map(|twr_index| twr_index.date)
+        .collect::<Vec<_>>();
+
+    let indexes = benchmark_indexes(conn, benchmark, &dates)?;
+
+    let (portfolio_base, benchmark_base) = twr_indexes
+        .iter()
+        .zip(&indexes)
+        .find_map(|(twr_index, index)| match index {
+            Some(index) if *index > 0.0 => Some((twr_index.index, *index)),
+            _ => None,
+        })
+        .unwrap_or((1.0, 0.0));
+
+    Ok(twr_indexes
+        .into_iter()
+        .zip(indexes)
+        .map(|(twr_index, index)| BenchmarkComparison {
+            date: twr_index.date,
+            portfolio: twr_index.index / portfolio_base,
+            benchmark: index
+                .filter(|index| *index > 0.0)
+                .map(|index| index / benchmark_base),
+        })
+        .collect())
+}
@@ -0,0 +1,151 @@
+use crate::{
+    models::{adjustment, corporate_actions, custody_fees, incomes, position, Assetable},
+    schema::trades,
+};
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::NaiveDate;
+use diesel::prelude::*;
+
+pub struct AssetReturn {
+    pub assetable: Assetable,
+    pub xirr: Option<f64>,
+}
+
+pub struct PortfolioReturns {
+    pub xirr: Option<f64>,
+    pub assets: Vec<AssetReturn>,
+}
+
+pub fn run(
+    conn: &PgConnection,
+    portfolio_id: i32,
+    today: NaiveDate,
+) -> QueryResult<PortfolioReturns> {
+    let mut trades = trades::table
+        .select((
+            trades::asset_id,
+            trades::date,
+            trades::price,
+            trades::quantity,
+            trades::brokerage_fee + trades::emoluments,
+        ))
+        .filter(trades::portfolio_id.eq(portfolio_id))
+        .filter(trades::date.le(today))
+        .order(trades::date)
+        .load::<Trade>(conn)?;
+
+    // Trade amounts don't change with corporate actions, only the asset they count for
+    let actions = corporate_actions(conn, today)?;
+
+    for trade in &mut trades {
+        trade.asset_id = adjustment(&actions, trade.asset_id, trade.date, today).0;
+    }
+
+    let position = position(conn, portfolio_id, today)?;
+    let incomes = incomes(conn, portfolio_id, today)?;
+    let custody_fees = custody_fees(conn, portfolio_id, today)?;
+
+    let mut portfolio_flows = vec![];
+    let mut assets = vec![];
+
+    for asset_position in position.assets.into_iter().chain(position.settled) {
+        let asset_id = asset_position.assetable.id();
+
+        let mut flows = trades
+            .iter()
+            .filter(|trade| trade.asset_id == asset_id)
+            .map(|trade| (trade.date, -(&trade.quantity * &trade.price) - &trade.fees))
+            .chain(
+                incomes
+                    .iter()
+                    .filter(|income| income.asset_id == asset_id)
+                    .map(|income| (income.date, income.amount.clone())),
+            )
+            .chain(
+                custody_fees
+                    .iter()
+                    .filter(|fee| fee.asset_id == asset_id)
+                    .map(|fee| (fee.date, -fee.amount.clone())),
+            )
+            .collect::<Vec<_>>();
+
+        flows.push((today, asset_position.amount));
+        portfolio_flows.extend(flows.iter().cloned());
+
+        assets.push(AssetReturn {
+            assetable: asset_position.assetable,
+            xirr: xirr(&flows),
+        });
+    }
+
+    Ok(PortfolioReturns {
+        xirr: xirr(&portfolio_flows),
+        assets,
+    })
+}
+
+// Money put in is negative and money taken out (or still held) is positive
+fn xirr(flows: &[(NaiveDate, BigDecimal)]) -> Option<f64> {
+    let first_date = flows.iter().map(|(date, _)| *date).min()?;
+
+    let flows = flows
+        .iter()
+        .map(|(date, amount)| {
+            (
+                (*date - first_date).num_days() as f64 / 365.0,
+                amount.to_f64().unwrap_or(0.0),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    if flows.iter().all(|(years, _)| *years == 0.0) {
+        return None;
+    }
+
+    let npv = |rate: f64| -> f64 {
+        flows
+            .iter()
+            .map(|(years, amount)| amount / (1.0 + rate).powf(*years))
+            .sum()
+    };
+
+    let mut low = XIRR_LOWER_BOUND;
+    let mut high = 1.0;
+
+    while npv(low).signum() == npv(high).signum() {
+        high *= 2.0;
+        if high > XIRR_UPPER_BOUND {
+            return None;
+        }
+    }
+
+    for _ in 0..XIRR_MAX_ITERATIONS {
+        let middle = (low + high) / 2.0;
+
+        if npv(middle).signum() == npv(low).signum() {
+            low = middle;
+        } else {
+            high = middle;
+        }
+
+        if high - low < XIRR_PRECISION {
+            break;
+        }
+    }
+
+    Some((low + high) / 2.0)
+}
+
+#[derive(Queryable)]
+struct Trade {
+    asset_id: i32,
+    date: NaiveDate,
+    price: BigDecimal,
+    quantity: BigDecimal,
+    fees: BigDecimal,
+}
+
+const XIRR_LOWER_BOUND: f64 = -0.9999;
+const XIRR_UPPER_BOUND: f64 = 1e6;
+const XIRR_MAX_ITERATIONS: usize = 200;
+const XIRR_PRECISION: f64 = 1e-9;
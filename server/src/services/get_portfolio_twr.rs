@@ -0,0 +1,91 @@
+use crate::services::get_portfolio_amounts::{self, PortfolioAmount};
+use bigdecimal::ToPrimitive;
+use chrono::{Datelike, NaiveDate};
+use diesel::prelude::*;
+use itertools::Itertools;
+
+pub struct TwrIndex {
+    pub date: NaiveDate,
+    pub index: f64,
+}
+
+pub struct ChainedReturn {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub value: f64,
+}
+
+pub struct PortfolioTwr {
+    pub indexes: Vec<TwrIndex>,
+    pub monthly: Vec<ChainedReturn>,
+    pub yearly: Vec<ChainedReturn>,
+}
+
+pub fn run(conn: &PgConnection, portfolio_id: i32, today: NaiveDate) -> QueryResult<PortfolioTwr> {
+    let amounts = get_portfolio_amounts::run(conn, portfolio_id, today)?;
+    let indexes = twr_indexes(&amounts);
+
+    Ok(PortfolioTwr {
+        monthly: chain(&indexes, |date| (date.year(), date.month())),
+        yearly: chain(&indexes, |date| (date.year(), 1)),
+        indexes,
+    })
+}
+
+// The invested amount only changes on trade dates, so chaining the days
+// is the same as breaking the sub-periods at every trade. The day's flow
+// is taken out of its closing value, as if it happened at the end of it,
+// and the income received so far is kept as part of the value.
+fn twr_indexes(amounts: &[PortfolioAmount]) -> Vec<TwrIndex> {
+    let mut indexes = vec![];
+    let mut index = 1.0;
+
+    for (i, amount) in amounts.iter().enumerate() {
+        if i > 0 {
+            let previous = &amounts[i - 1];
+            let flow = &amount.invested - &previous.invested;
+
+            let start_value = (&previous.gross_total + &previous.income)
+                .to_f64()
+                .unwrap_or(0.0);
+
+            let end_value = (&amount.gross_total + &amount.income - &flow)
+                .to_f64()
+                .unwrap_or(0.0);
+
+            if start_value > 0.0 {
+                index *= end_value / start_value;
+            }
+        }
+
+        indexes.push(TwrIndex {
+            date: amount.date,
+            index,
+        });
+    }
+
+    indexes
+}
+
+fn chain<K: PartialEq>(
+    indexes: &[TwrIndex],
+    period: impl Fn(NaiveDate) -> K,
+) -> Vec<ChainedReturn> {
+    let mut returns = vec![];
+    let mut base_index = 1.0;
+
+    for (_, group) in &indexes.iter().group_by(|twr| period(twr.date)) {
+        let group = group.collect::<Vec<_>>();
+        let (first, last) = (group[0], group[group.len() - 1]);
+
+        returns.push(ChainedReturn {
+            start: first.date,
+            end: last.date,
+            value: last.index / base_index - 1.0,
+        });
+
+        base_index = last.index;
+    }
+
+    returns
+}
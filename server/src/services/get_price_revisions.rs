@@ -0,0 +1,29 @@
+use crate::models::{
+    known_asset_id, price_revisions, ticker_asset_id, treasury_bond_asset_id, PriceRevision,
+};
+use chrono::NaiveDate;
+use diesel::prelude::*;
+
+// Exchange listed assets are found by their ticker and treasury bonds by key and maturity,
+// while any other asset, like funds and cryptos, is found by its id
+pub enum Asset {
+    Id(i32),
+    Ticker(String),
+    TreasuryBond(String, NaiveDate),
+}
+
+pub fn run(
+    conn: &PgConnection,
+    asset: &Asset,
+    date: Option<NaiveDate>,
+) -> QueryResult<Vec<PriceRevision>> {
+    let asset_id = match asset {
+        Asset::Id(asset_id) => known_asset_id(conn, *asset_id)?,
+        Asset::Ticker(ticker) => ticker_asset_id(conn, ticker)?,
+        Asset::TreasuryBond(key, maturity_date) => {
+            treasury_bond_asset_id(conn, key, *maturity_date)?
+        }
+    };
+
+    price_revisions(conn, asset_id, date)
+}
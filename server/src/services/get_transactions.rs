@@ -12,6 +12,7 @@ pub struct Transaction {
     pub price: BigDecimal,
     pub quantity: BigDecimal,
     pub amount: BigDecimal,
+    pub fees: BigDecimal,
 }
 
 pub fn run(conn: &PgConnection, portfolio_id: i32) -> QueryResult<Vec<Transaction>> {
@@ -21,6 +22,7 @@ pub fn run(conn: &PgConnection, portfolio_id: i32) -> QueryResult<Vec<Transactio
             trades::date,
             trades::price,
             trades::quantity,
+            trades::brokerage_fee + trades::emoluments,
         ))
         .filter(trades::portfolio_id.eq(portfolio_id))
         .order((trades::date.desc(), trades::asset_id))
@@ -41,6 +43,7 @@ pub fn run(conn: &PgConnection, portfolio_id: i32) -> QueryResult<Vec<Transactio
             amount: &trade.price * &trade.quantity,
             quantity: trade.quantity,
             price: trade.price,
+            fees: trade.fees,
             date: trade.date,
             assetable,
         })
@@ -53,4 +56,5 @@ struct Trade {
     date: NaiveDate,
     price: BigDecimal,
     quantity: BigDecimal,
+    fees: BigDecimal,
 }
@@ -0,0 +1,80 @@
+use crate::models::{
+    finish_assetless_import_run, register_benchmark_values, register_import_run, Benchmark,
+};
+use bigdecimal::BigDecimal;
+use bytes::{buf::ext::BufExt, Bytes};
+use chrono::NaiveDate;
+use csv::ReaderBuilder;
+use diesel::PgConnection;
+use serde::Deserialize;
+use std::str::FromStr;
+
+pub enum Error {
+    Writing(diesel::result::Error),
+    Parsing(String),
+}
+
+#[derive(Deserialize)]
+struct Line {
+    #[serde(rename = "data")]
+    date: String,
+    #[serde(rename = "valor")]
+    value: String,
+}
+
+fn read_lines(bytes: Bytes) -> Result<Vec<Line>, String> {
+    let is_json = bytes.iter().find(|byte| !byte.is_ascii_whitespace()) == Some(&b'[');
+
+    if is_json {
+        return serde_json::from_slice(&bytes).map_err(|e| format!("Body is bad: {}", e));
+    }
+
+    ReaderBuilder::new()
+        .delimiter(b';')
+        .from_reader(bytes.reader())
+        .into_deserialize()
+        .collect::<Result<_, _>>()
+        .map_err(|e| format!("Some line is bad: {}", e))
+}
+
+fn parse(bytes: Bytes) -> Result<Vec<(NaiveDate, BigDecimal)>, String> {
+    let mut values = vec![];
+
+    for line in read_lines(bytes)? {
+        let date = NaiveDate::parse_from_str(&line.date, "%d/%m/%Y")
+            .map_err(|e| format!("Some line's date is bad: {}", e))?;
+
+        let value = BigDecimal::from_str(&line.value.replace(",", "."))
+            .map_err(|e| format!("Some line's value is bad: {}", e))?;
+
+        values.push((date, value));
+    }
+
+    if values.is_empty() {
+        return Err(String::from("There were no values to import"));
+    }
+
+    Ok(values)
+}
+
+fn import(conn: &PgConnection, benchmark: Benchmark, file: Bytes) -> Result<usize, Error> {
+    parse(file).map_err(Error::Parsing).and_then(|values| {
+        register_benchmark_values(conn, benchmark, values).map_err(Error::Writing)
+    })
+}
+
+pub fn run(conn: &PgConnection, benchmark: Benchmark, file: Bytes) -> Result<usize, Error> {
+    let import_run_id = register_import_run(conn, "benchmarks").map_err(Error::Writing)?;
+    let result = import(conn, benchmark, file);
+
+    finish_assetless_import_run(
+        conn,
+        import_run_id,
+        result.as_ref().map(|_| ()).map_err(|e| match e {
+            Error::Parsing(e) => format!("Parsing: {}", e),
+            Error::Writing(e) => format!("Writing: {}", e),
+        }),
+    );
+
+    result
+}
@@ -0,0 +1,83 @@
+use crate::models::{
+    finish_assetless_import_run, register_cash_movements, register_import_run, CashKind,
+    CashMovement,
+};
+use bigdecimal::{BigDecimal, Zero};
+use bytes::{buf::ext::BufExt, Bytes};
+use chrono::NaiveDate;
+use csv::Reader;
+use diesel::PgConnection;
+use serde::Deserialize;
+use std::str::FromStr;
+
+#[derive(Deserialize)]
+struct Line {
+    #[serde(rename = "Data")]
+    date: String,
+    #[serde(rename = "Tipo")]
+    kind: String,
+    #[serde(rename = "Valor")]
+    amount: String,
+}
+
+pub enum Error {
+    Writing(diesel::result::Error),
+    Parsing(String),
+}
+
+fn parse(bytes: Bytes) -> Result<Vec<CashMovement>, String> {
+    let lines = Reader::from_reader(bytes.reader()).into_deserialize();
+
+    let mut movements = vec![];
+
+    for line in lines {
+        let line: Line = line.map_err(|e| format!("Some line is bad: {}", e))?;
+
+        let date = NaiveDate::parse_from_str(&line.date, "%d/%m/%Y")
+            .map_err(|e| format!("Some line's date is bad: {}", e))?;
+
+        let kind = match line.kind.as_str() {
+            "Depósito" => CashKind::Deposit,
+            "Retirada" => CashKind::Withdrawal,
+            "Taxa" => CashKind::Fee,
+            kind => return Err(format!("Some line's kind is bad: {}", kind)),
+        };
+
+        let amount = BigDecimal::from_str(&line.amount)
+            .map_err(|e| format!("Some line's amount is bad: {}", e))?;
+
+        if amount <= BigDecimal::zero() {
+            return Err(format!("Some line's amount is not positive: {}", amount));
+        }
+
+        movements.push(CashMovement { date, kind, amount });
+    }
+
+    if movements.is_empty() {
+        return Err(String::from("There were no movements to import"));
+    }
+
+    Ok(movements)
+}
+
+fn import(conn: &PgConnection, portfolio_id: i32, csv: Bytes) -> Result<usize, Error> {
+    parse(csv).map_err(Error::Parsing).and_then(|movements| {
+        register_cash_movements(conn, portfolio_id, &movements).map_err(Error::Writing)
+    })
+}
+
+pub fn run(conn: &PgConnection, portfolio_id: i32, csv: Bytes) -> Result<usize, Error> {
+    let import_run_id = register_import_run(conn, "cash-movements").map_err(Error::Writing)?;
+    let result = import(conn, portfolio_id, csv);
+
+    finish_assetless_import_run(
+        conn,
+        import_run_id,
+        result.as_ref().map(|_| ()).map_err(|e| match e {
+            Error::Parsing(e) => format!("Parsing: {}", e),
+            Error::Writing(e) => format!("Writing: {}", e),
+        }),
+    );
+
+    result
+}
@@ -0,0 +1,122 @@
+use crate::models::{
+    finish_assetless_import_run, register_corporate_actions, register_import_run, CorporateAction,
+    CorporateActionKind,
+};
+use bigdecimal::{BigDecimal, Zero};
+use bytes::{buf::ext::BufExt, Bytes};
+use chrono::NaiveDate;
+use csv::Reader;
+use diesel::PgConnection;
+use serde::Deserialize;
+use std::str::FromStr;
+
+// The factor is N for a 1:N split, N for a N:1 grouping, the new units received per unit
+// for a merger, and is not needed for a ticker change
+#[derive(Deserialize)]
+struct Line {
+    #[serde(rename = "Ticker")]
+    ticker: String,
+    #[serde(rename = "Tipo")]
+    kind: String,
+    #[serde(rename = "Data")]
+    date: String,
+    #[serde(rename = "Fator", default)]
+    factor: Option<String>,
+    #[serde(rename = "NovoTicker", default)]
+    new_ticker: Option<String>,
+}
+
+pub enum Error {
+    Writing(diesel::result::Error),
+    Parsing(String),
+}
+
+fn parse_factor(factor: Option<&str>) -> Result<BigDecimal, String> {
+    let factor = factor
+        .filter(|factor| !factor.is_empty())
+        .ok_or_else(|| String::from("Some line's factor is missing"))?;
+
+    let factor = BigDecimal::from_str(&factor.replace(",", "."))
+        .map_err(|e| format!("Some line's factor is bad: {}", e))?;
+
+    if factor <= BigDecimal::zero() {
+        return Err(format!("Some line's factor is not positive: {}", factor));
+    }
+
+    Ok(factor)
+}
+
+fn parse(bytes: Bytes) -> Result<Vec<CorporateAction>, String> {
+    let lines = Reader::from_reader(bytes.reader()).into_deserialize();
+
+    let mut actions = vec![];
+
+    for line in lines {
+        let line: Line = line.map_err(|e| format!("Some line is bad: {}", e))?;
+
+        let date = NaiveDate::parse_from_str(&line.date, "%d/%m/%Y")
+            .map_err(|e| format!("Some line's date is bad: {}", e))?;
+
+        let new_ticker = line.new_ticker.filter(|new_ticker| !new_ticker.is_empty());
+
+        let (kind, ratio, new_ticker) = match line.kind.as_str() {
+            "Desdobramento" => (
+                CorporateActionKind::Split,
+                parse_factor(line.factor.as_deref())?,
+                None,
+            ),
+            "Grupamento" => (
+                CorporateActionKind::ReverseSplit,
+                BigDecimal::from(1) / parse_factor(line.factor.as_deref())?,
+                None,
+            ),
+            "Mudança de Ticker" => (
+                CorporateActionKind::Rename,
+                BigDecimal::from(1),
+                Some(new_ticker.ok_or_else(|| String::from("Some line's new ticker is missing"))?),
+            ),
+            "Incorporação" => (
+                CorporateActionKind::Merger,
+                parse_factor(line.factor.as_deref())?,
+                Some(new_ticker.ok_or_else(|| String::from("Some line's new ticker is missing"))?),
+            ),
+            kind => return Err(format!("Some line's kind is bad: {}", kind)),
+        };
+
+        actions.push(CorporateAction {
+            ticker: line.ticker,
+            new_ticker,
+            ratio,
+            kind,
+            date,
+        });
+    }
+
+    if actions.is_empty() {
+        return Err(String::from("There were no corporate actions to import"));
+    }
+
+    Ok(actions)
+}
+
+fn import(conn: &PgConnection, csv: Bytes) -> Result<usize, Error> {
+    parse(csv)
+        .map_err(Error::Parsing)
+        .and_then(|actions| register_corporate_actions(conn, &actions).map_err(Error::Writing))
+}
+
+pub fn run(conn: &PgConnection, csv: Bytes) -> Result<usize, Error> {
+    let import_run_id = register_import_run(conn, "corporate-actions").map_err(Error::Writing)?;
+    let result = import(conn, csv);
+
+    finish_assetless_import_run(
+        conn,
+        import_run_id,
+        result.as_ref().map(|_| ()).map_err(|e| match e {
+            Error::Parsing(e) => format!("Parsing: {}", e),
+            Error::Writing(e) => format!("Writing: {}", e),
+        }),
+    );
+
+    result
+}
@@ -0,0 +1,128 @@
+use crate::models::{
+    finish_import_run, register_etf_prices, register_import_run, tracked_etfs, PriceCounts,
+};
+use crate::services::price_sources::Error;
+use bigdecimal::BigDecimal;
+use bytes::Bytes;
+use chrono::NaiveDate;
+use diesel::PgConnection;
+use itertools::Itertools;
+use std::str::FromStr;
+
+type Results = Vec<(String, Result<PriceCounts, Error>)>;
+
+struct Quote<'a> {
+    ticker: &'a str,
+    date: NaiveDate,
+    price: BigDecimal,
+}
+
+// Fields are located by the 1-based inclusive positions of the B3 layout
+fn field(line: &[u8], start: usize, end: usize) -> Result<&str, String> {
+    line.get(start - 1..end)
+        .and_then(|bytes| std::str::from_utf8(bytes).ok())
+        .ok_or_else(|| format!("Some line is too short for field at {}-{}", start, end))
+}
+
+// Only quote records of the standard lot market are kept. Prices come with two implied
+// decimals and for the quotation factor of the asset, which is 1000 when quoted per thousand.
+fn parse_line(line: &[u8]) -> Result<Option<Quote<'_>>, String> {
+    if field(line, 1, 2)? != QUOTE_RECORD || field(line, 25, 27)? != STANDARD_LOT_MARKET {
+        return Ok(None);
+    }
+
+    let date = NaiveDate::parse_from_str(field(line, 3, 10)?, "%Y%m%d")
+        .map_err(|e| format!("Some line's date is bad: {}", e))?;
+
+    let ticker = field(line, 13, 24)?.trim_end();
+
+    let price = BigDecimal::from_str(field(line, 109, 121)?)
+        .map_err(|e| format!("Some line's price is bad: {}", e))?;
+
+    let factor = field(line, 211, 217)?
+        .parse::<i32>()
+        .map_err(|e| format!("Some line's quotation factor is bad: {}", e))?;
+
+    if factor <= 0 {
+        return Err(format!("Some line's quotation factor is bad: {}", factor));
+    }
+
+    Ok(Some(Quote {
+        price: price / BigDecimal::from(100 * factor),
+        ticker,
+        date,
+    }))
+}
+
+fn parse<'a>(bytes: &'a Bytes, tickers: &[String]) -> Result<Vec<Quote<'a>>, String> {
+    let mut quotes = vec![];
+
+    for line in bytes.split(|byte| *byte == b'\n') {
+        let line = if line.ends_with(b"\r") {
+            &line[..line.len() - 1]
+        } else {
+            line
+        };
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(quote) = parse_line(line)? {
+            if tickers.iter().any(|ticker| ticker == quote.ticker) {
+                quotes.push(quote);
+            }
+        }
+    }
+
+    Ok(quotes)
+}
+
+fn import(conn: &PgConnection, import_run_id: i32, bytes: Bytes) -> Result<Results, Error> {
+    let tickers = tracked_etfs(conn)
+        .map_err(Error::Writing)?
+        .into_iter()
+        .map(|tracked_etf| tracked_etf.ticker)
+        .collect::<Vec<_>>();
+
+    let quotes = parse(&bytes, &tickers).map_err(Error::Parsing)?;
+
+    if quotes.is_empty() {
+        return Err(Error::Parsing(String::from(
+            "There were no quotes to import",
+        )));
+    }
+
+    Ok(quotes
+        .into_iter()
+        .sorted_by_key(|quote| (quote.ticker, quote.date))
+        .group_by(|quote| quote.ticker)
+        .into_iter()
+        .map(|(ticker, quotes)| {
+            let prices = quotes.map(|quote| (quote.date, quote.price)).collect();
+            let result =
+                register_etf_prices(conn, ticker, import_run_id, prices).map_err(Error::Writing);
+
+            (ticker.to_owned(), result)
+        })
+        .collect())
+}
+
+pub fn run(conn: &PgConnection, bytes: Bytes) -> Result<Results, Error> {
+    let import_run_id = register_import_run(conn, "cotahist").map_err(Error::Writing)?;
+    let result = import(conn, import_run_id, bytes);
+
+    finish_import_run(
+        conn,
+        import_run_id,
+        result.as_ref().map(Vec::as_slice).map_err(Error::message),
+        String::clone,
+        Error::message,
+    );
+
+    result
+}
+
+const QUOTE_RECORD: &str = "01";
+
+const STANDARD_LOT_MARKET: &str = "010";
@@ -0,0 +1,138 @@
+use crate::models::{
+    finish_assetless_import_run, register_crypto_trades, register_import_run, CryptoTrade,
+    Currency, TradeFees,
+};
+use bigdecimal::BigDecimal;
+use bytes::{buf::ext::BufExt, Bytes};
+use chrono::NaiveDate;
+use csv::Reader;
+use diesel::PgConnection;
+use serde::Deserialize;
+use std::str::FromStr;
+
+// Exchange trade history as exported by Binance, where amounts carry their asset
+// symbol as a suffix, like 0.00120000BTC and 45.12000000USDT, and the pair is both
+// symbols together, like BTCUSDT
+#[derive(Deserialize)]
+struct Line {
+    #[serde(rename = "Date(UTC)")]
+    date: String,
+    #[serde(rename = "Pair")]
+    pair: String,
+    #[serde(rename = "Side")]
+    side: String,
+    #[serde(rename = "Price")]
+    price: String,
+    #[serde(rename = "Executed")]
+    executed: String,
+    #[serde(rename = "Amount")]
+    amount: String,
+}
+
+pub enum Error {
+    Writing(diesel::result::Error),
+    Parsing(String),
+}
+
+// Symbols may start with digits, like 1INCH, so values are split by the symbol
+// known from the pair instead of where their letters start
+fn strip_symbol(value: &str, symbol: &str) -> Result<BigDecimal, String> {
+    if !value.ends_with(symbol) {
+        return Err(format!("Some line's value is not in {}: {}", symbol, value));
+    }
+
+    let number = value[..value.len() - symbol.len()].trim();
+
+    BigDecimal::from_str(&number.replace(",", ""))
+        .map_err(|e| format!("Some line's value is bad: {}", e))
+}
+
+// Dollar stablecoins are taken at par with the dollar
+fn quote_currency(symbol: &str) -> Option<Currency> {
+    match symbol {
+        "BRL" => Some(Currency::Brl),
+        "USD" | "USDT" | "USDC" | "BUSD" => Some(Currency::Usd),
+        _ => None,
+    }
+}
+
+// The quote is the known currency the pair ends with, and the traded symbol is what's left
+fn split_pair(pair: &str) -> Result<(&str, &str), String> {
+    QUOTE_SYMBOLS
+        .iter()
+        .find(|quote| pair.len() > quote.len() && pair.ends_with(*quote))
+        .map(|quote| (&pair[..pair.len() - quote.len()], *quote))
+        .ok_or_else(|| format!("Some line's pair is bad: {}", pair))
+}
+
+fn parse(bytes: Bytes) -> Result<Vec<CryptoTrade>, String> {
+    let lines = Reader::from_reader(bytes.reader()).into_deserialize();
+
+    let mut trades = vec![];
+
+    for line in lines {
+        let line: Line = line.map_err(|e| format!("Some line is bad: {}", e))?;
+
+        let date = line
+            .date
+            .get(..10)
+            .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+            .ok_or_else(|| format!("Some line's date is bad: {}", line.date))?;
+
+        let price = BigDecimal::from_str(&line.price.replace(",", ""))
+            .map_err(|e| format!("Some line's price is bad: {}", e))?;
+
+        let (symbol, quote) = split_pair(&line.pair)?;
+        let quantity = strip_symbol(&line.executed, symbol)?;
+        strip_symbol(&line.amount, quote)?;
+
+        let currency = quote_currency(quote)
+            .ok_or_else(|| format!("Some line's quote currency is bad: {}", quote))?;
+
+        let quantity = match line.side.as_str() {
+            "BUY" => quantity,
+            "SELL" => -quantity,
+            side => return Err(format!("Some line's side is bad: {}", side)),
+        };
+
+        trades.push(CryptoTrade {
+            symbol: symbol.to_owned(),
+            currency,
+            fees: TradeFees::default(),
+            quantity,
+            price,
+            date,
+        });
+    }
+
+    if trades.is_empty() {
+        return Err(String::from("There were no trades to import"));
+    }
+
+    Ok(trades)
+}
+
+fn import(conn: &PgConnection, portfolio_id: i32, csv: Bytes) -> Result<usize, Error> {
+    parse(csv).map_err(Error::Parsing).and_then(|trades| {
+        register_crypto_trades(conn, portfolio_id, &trades).map_err(Error::Writing)
+    })
+}
+
+pub fn run(conn: &PgConnection, portfolio_id: i32, csv: Bytes) -> Result<usize, Error> {
+    let import_run_id = register_import_run(conn, "crypto-trades").map_err(Error::Writing)?;
+    let result = import(conn, portfolio_id, csv);
+
+    finish_assetless_import_run(
+        conn,
+        import_run_id,
+        result.as_ref().map(|_| ()).map_err(|e| match e {
+            Error::Parsing(e) => format!("Parsing: {}", e),
+            Error::Writing(e) => format!("Writing: {}", e),
+        }),
+    );
+
+    result
+}
+
+// Longer symbols come first so BUSD isn't taken for USD
+const QUOTE_SYMBOLS: [&str; 5] = ["USDT", "USDC", "BUSD", "BRL", "USD"];
@@ -1,34 +1,54 @@
-use crate::models::register_etf_prices;
+use crate::models::{
+    finish_import_run, last_etf_price_date, register_etf_prices, register_import_run, tracked_etfs,
+    PriceCounts, TrackedEtf,
+};
+use crate::services::price_sources::{Error, PriceSource, Registry};
 use actix_web::client::Client;
 use bigdecimal::BigDecimal;
+use bytes::{buf::ext::BufExt, Bytes};
 use chrono::NaiveDate;
+use csv::Reader;
 use diesel::PgConnection;
+use futures::future::{FutureExt, LocalBoxFuture};
 use scraper::{Html, Selector};
+use serde::Deserialize;
 use std::str::FromStr;
 
-pub enum Error {
-    Network(actix_web::client::SendRequestError),
-    Status(actix_web::http::StatusCode),
-    Payload(actix_web::client::PayloadError),
-    Writing(diesel::result::Error),
-    Parsing(String),
-}
+type Prices = Vec<(NaiveDate, BigDecimal)>;
 
 #[allow(non_snake_case)]
 #[derive(serde::Serialize)]
-struct FormParams {
-    curr_id: &'static str,
-    smlID: &'static str,
-    header: &'static str,
-    st_date: &'static str,
-    end_date: &'static str,
+struct FormParams<'a> {
+    curr_id: &'a str,
+    smlID: &'a str,
+    header: String,
+    st_date: String,
+    end_date: String,
     interval_sec: &'static str,
     sort_col: &'static str,
     sort_ord: &'static str,
     action: &'static str,
 }
 
-async fn fetch(form_params: &FormParams) -> Result<bytes::Bytes, Error> {
+fn form_params(
+    tracked_etf: &TrackedEtf,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> FormParams<'_> {
+    FormParams {
+        curr_id: &tracked_etf.curr_id,
+        smlID: &tracked_etf.sml_id,
+        header: format!("{}+Historical+Data", tracked_etf.ticker),
+        st_date: start_date.format(FORM_DATE_FORMAT).to_string(),
+        end_date: end_date.format(FORM_DATE_FORMAT).to_string(),
+        interval_sec: "Daily",
+        sort_col: "date",
+        sort_ord: "DESC",
+        action: "historical_data",
+    }
+}
+
+async fn fetch_investing(form_params: &FormParams<'_>) -> Result<Bytes, Error> {
     let mut response = Client::default()
         .post("https://br.investing.com/instruments/HistoricalDataAjax")
         .header("Accept", "text/plain, */*; q=0.01")
@@ -58,7 +78,7 @@ async fn fetch(form_params: &FormParams) -> Result<bytes::Bytes, Error> {
         .map_err(Error::Payload)
 }
 
-fn parse(bytes: bytes::Bytes) -> Result<Vec<(NaiveDate, BigDecimal)>, Error> {
+fn parse_investing(bytes: Bytes) -> Result<Prices, Error> {
     let string = std::str::from_utf8(&bytes)
         .map_err(|_| Error::Parsing("Body is not a utf8 string".into()))?;
 
@@ -111,69 +131,188 @@ fn parse(bytes: bytes::Bytes) -> Result<Vec<(NaiveDate, BigDecimal)>, Error> {
     Ok(prices)
 }
 
+// Yahoo quotes B3 listings with a .SA suffix, and leaves days without trades as null
+#[derive(Deserialize)]
+struct YahooLine {
+    #[serde(rename = "Date")]
+    date: String,
+    #[serde(rename = "Close")]
+    close: String,
+}
+
+async fn fetch_yahoo(
+    ticker: &str,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<Bytes, Error> {
+    let url = format!(
+        "https://query1.finance.yahoo.com/v7/finance/download/{}.SA?period1={}&period2={}&interval=1d&events=history",
+        ticker,
+        start_date.and_hms(0, 0, 0).timestamp(),
+        end_date.succ().and_hms(0, 0, 0).timestamp(),
+    );
+
+    let mut response = Client::default()
+        .get(url)
+        .send()
+        .await
+        .map_err(Error::Network)?;
+
+    let status = response.status();
+    if status != 200 {
+        return Err(Error::Status(status));
+    }
+
+    response
+        .body()
+        .limit(PAYLOAD_LIMIT)
+        .await
+        .map_err(Error::Payload)
+}
+
+fn parse_yahoo(bytes: Bytes) -> Result<Prices, Error> {
+    let lines = Reader::from_reader(bytes.reader()).into_deserialize();
+
+    let mut prices = vec![];
+    for line in lines {
+        let line: YahooLine =
+            line.map_err(|e| Error::Parsing(format!("Some line is bad: {}", e)))?;
+
+        if line.close == "null" {
+            continue;
+        }
+
+        let date = NaiveDate::parse_from_str(&line.date, "%Y-%m-%d")
+            .map_err(|e| Error::Parsing(format!("Some line's date is bad: {}", e)))?;
+
+        let price = BigDecimal::from_str(&line.close)
+            .map_err(|e| Error::Parsing(format!("Some line's price is bad: {}", e)))?;
+
+        prices.push((date, price));
+    }
+
+    Ok(prices)
+}
+
+struct Investing;
+
+impl PriceSource for Investing {
+    type Asset = TrackedEtf;
+    type Prices = Prices;
+
+    fn name(&self) -> &'static str {
+        "investing"
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        tracked_etf: &'a TrackedEtf,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> LocalBoxFuture<'a, Result<Prices, Error>> {
+        async move {
+            fetch_investing(&form_params(tracked_etf, start_date, end_date))
+                .await
+                .and_then(parse_investing)
+        }
+        .boxed_local()
+    }
+}
+
+struct Yahoo;
+
+impl PriceSource for Yahoo {
+    type Asset = TrackedEtf;
+    type Prices = Prices;
+
+    fn name(&self) -> &'static str {
+        "yahoo"
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        tracked_etf: &'a TrackedEtf,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> LocalBoxFuture<'a, Result<Prices, Error>> {
+        async move {
+            fetch_yahoo(&tracked_etf.ticker, start_date, end_date)
+                .await
+                .and_then(parse_yahoo)
+        }
+        .boxed_local()
+    }
+}
+
+// Investing is the reference for every ETF, with Yahoo as the fallback, except for the
+// ETFs tracked without investing.com identifiers, which go to Yahoo first
+fn registry(tracked_etfs: &[TrackedEtf]) -> Registry<TrackedEtf, Prices> {
+    tracked_etfs
+        .iter()
+        .filter(|tracked_etf| tracked_etf.curr_id.is_empty() || tracked_etf.sml_id.is_empty())
+        .fold(
+            Registry::new(vec![Box::new(Investing), Box::new(Yahoo)]),
+            |registry, tracked_etf| registry.order(&tracked_etf.ticker, &["yahoo", "investing"]),
+        )
+}
+
 fn write(
     conn: &PgConnection,
+    import_run_id: i32,
     ticker: &str,
-    lines: Vec<(NaiveDate, BigDecimal)>,
-) -> Result<usize, Error> {
-    register_etf_prices(conn, ticker, lines).map_err(Error::Writing)
+    lines: Prices,
+) -> Result<PriceCounts, Error> {
+    register_etf_prices(conn, ticker, import_run_id, lines).map_err(Error::Writing)
 }
 
-pub async fn run(conn: &PgConnection) -> Vec<(&'static str, Result<usize, Error>)> {
-    let tickers_params = [
-        ("BOVA11", BOVA11_FORM_PARAMS),
-        ("SMAL11", SMAL11_FORM_PARAMS),
-        ("IVVB11", IVVB11_FORM_PARAMS),
-    ];
+// The window starts at the last stored price, so it gets refreshed along with the new ones
+async fn import(
+    conn: &PgConnection,
+    import_run_id: i32,
+    today: NaiveDate,
+) -> Result<Vec<(String, Result<PriceCounts, Error>)>, Error> {
+    let tracked_etfs = tracked_etfs(conn).map_err(Error::Writing)?;
+    let registry = registry(&tracked_etfs);
 
     let mut results = vec![];
 
-    for (ticker, params) in tickers_params.iter() {
-        let result = fetch(params)
-            .await
-            .and_then(parse)
-            .and_then(|lines| write(conn, ticker, lines));
+    for tracked_etf in tracked_etfs {
+        let result = match last_etf_price_date(conn, &tracked_etf.ticker) {
+            Err(e) => Err(Error::Writing(e)),
+            Ok(last_date) => {
+                let start_date = last_date.unwrap_or_else(|| NaiveDate::from_ymd(2000, 1, 1));
+
+                registry
+                    .fetch(&tracked_etf.ticker, &tracked_etf, start_date, today)
+                    .await
+                    .and_then(|(_, lines)| write(conn, import_run_id, &tracked_etf.ticker, lines))
+            }
+        };
 
-        results.push((*ticker, result));
+        results.push((tracked_etf.ticker, result));
     }
 
-    results
+    Ok(results)
 }
 
-const PAYLOAD_LIMIT: usize = 16777216;
+pub async fn run(
+    conn: &PgConnection,
+    today: NaiveDate,
+) -> Result<Vec<(String, Result<PriceCounts, Error>)>, Error> {
+    let import_run_id = register_import_run(conn, "etfs-prices").map_err(Error::Writing)?;
+    let result = import(conn, import_run_id, today).await;
 
-const BOVA11_FORM_PARAMS: FormParams = FormParams {
-    curr_id: "39004",
-    smlID: "2514218",
-    header: "BOVA11+Historical+Data",
-    st_date: "01/01/2000",
-    end_date: "01/01/2022",
-    interval_sec: "Daily",
-    sort_col: "date",
-    sort_ord: "DESC",
-    action: "historical_data",
-};
+    finish_import_run(
+        conn,
+        import_run_id,
+        result.as_ref().map(Vec::as_slice).map_err(Error::message),
+        String::clone,
+        Error::message,
+    );
 
-const SMAL11_FORM_PARAMS: FormParams = FormParams {
-    curr_id: "39013",
-    smlID: "2514317",
-    header: "SMAL11+Historical+Data",
-    st_date: "01/01/2000",
-    end_date: "01/01/2022",
-    interval_sec: "Daily",
-    sort_col: "date",
-    sort_ord: "DESC",
-    action: "historical_data",
-};
+    result
+}
 
-const IVVB11_FORM_PARAMS: FormParams = FormParams {
-    curr_id: "956435",
-    smlID: "2585374",
-    header: "IVVB11+Historical+Data",
-    st_date: "01/01/2000",
-    end_date: "01/01/2022",
-    interval_sec: "Daily",
-    sort_col: "date",
-    sort_ord: "DESC",
-    action: "historical_data",
-};
+const PAYLOAD_LIMIT: usize = 16777216;
+
+const FORM_DATE_FORMAT: &str = "%m/%d/%Y";
@@ -0,0 +1,81 @@
+use crate::models::{
+    finish_assetless_import_run, register_exchange_rates, register_import_run, Currency,
+};
+use bigdecimal::BigDecimal;
+use bytes::{buf::ext::BufExt, Bytes};
+use chrono::NaiveDate;
+use csv::ReaderBuilder;
+use diesel::PgConnection;
+use serde::Deserialize;
+use std::str::FromStr;
+
+pub enum Error {
+    Writing(diesel::result::Error),
+    Parsing(String),
+}
+
+// Same layout as the Banco Central series used for benchmarks (PTAX is series 1 for USD)
+#[derive(Deserialize)]
+struct Line {
+    #[serde(rename = "data")]
+    date: String,
+    #[serde(rename = "valor")]
+    rate: String,
+}
+
+fn read_lines(bytes: Bytes) -> Result<Vec<Line>, String> {
+    let is_json = bytes.iter().find(|byte| !byte.is_ascii_whitespace()) == Some(&b'[');
+
+    if is_json {
+        return serde_json::from_slice(&bytes).map_err(|e| format!("Body is bad: {}", e));
+    }
+
+    ReaderBuilder::new()
+        .delimiter(b';')
+        .from_reader(bytes.reader())
+        .into_deserialize()
+        .collect::<Result<_, _>>()
+        .map_err(|e| format!("Some line is bad: {}", e))
+}
+
+fn parse(bytes: Bytes) -> Result<Vec<(NaiveDate, BigDecimal)>, String> {
+    let mut rates = vec![];
+
+    for line in read_lines(bytes)? {
+        let date = NaiveDate::parse_from_str(&line.date, "%d/%m/%Y")
+            .map_err(|e| format!("Some line's date is bad: {}", e))?;
+
+        let rate = BigDecimal::from_str(&line.rate.replace(",", "."))
+            .map_err(|e| format!("Some line's rate is bad: {}", e))?;
+
+        rates.push((date, rate));
+    }
+
+    if rates.is_empty() {
+        return Err(String::from("There were no rates to import"));
+    }
+
+    Ok(rates)
+}
+
+fn import(conn: &PgConnection, currency: Currency, file: Bytes) -> Result<usize, Error> {
+    parse(file)
+        .map_err(Error::Parsing)
+        .and_then(|rates| register_exchange_rates(conn, currency, rates).map_err(Error::Writing))
+}
+
+pub fn run(conn: &PgConnection, currency: Currency, file: Bytes) -> Result<usize, Error> {
+    let import_run_id = register_import_run(conn, "exchange-rates").map_err(Error::Writing)?;
+    let result = import(conn, currency, file);
+
+    finish_assetless_import_run(
+        conn,
+        import_run_id,
+        result.as_ref().map(|_| ()).map_err(|e| match e {
+            Error::Parsing(e) => format!("Parsing: {}", e),
+            Error::Writing(e) => format!("Writing: {}", e),
+        }),
+    );
+
+    result
+}
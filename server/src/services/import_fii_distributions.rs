@@ -0,0 +1,85 @@
+use crate::models::{
+    finish_assetless_import_run, register_import_run, register_income_events, IncomeEvent,
+    IncomeKind,
+};
+use bigdecimal::BigDecimal;
+use bytes::{buf::ext::BufExt, Bytes};
+use chrono::NaiveDate;
+use csv::Reader;
+use diesel::PgConnection;
+use serde::Deserialize;
+use std::str::FromStr;
+
+#[derive(Deserialize)]
+struct Line {
+    #[serde(rename = "Ticker")]
+    ticker: String,
+    #[serde(rename = "DataCom")]
+    date: String,
+    #[serde(rename = "DataPagamento")]
+    payment_date: String,
+    #[serde(rename = "Valor")]
+    value: String,
+}
+
+pub enum Error {
+    Writing(diesel::result::Error),
+    Parsing(String),
+}
+
+fn parse(bytes: Bytes) -> Result<Vec<IncomeEvent>, String> {
+    let lines = Reader::from_reader(bytes.reader()).into_deserialize();
+
+    let mut distributions = vec![];
+
+    for line in lines {
+        let line: Line = line.map_err(|e| format!("Some line is bad: {}", e))?;
+
+        let date = NaiveDate::parse_from_str(&line.date, "%d/%m/%Y")
+            .map_err(|e| format!("Some line's date is bad: {}", e))?;
+
+        let payment_date = NaiveDate::parse_from_str(&line.payment_date, "%d/%m/%Y")
+            .map_err(|e| format!("Some line's payment date is bad: {}", e))?;
+
+        let value = BigDecimal::from_str(&line.value.replace(",", "."))
+            .map_err(|e| format!("Some line's value is bad: {}", e))?;
+
+        distributions.push(IncomeEvent {
+            ticker: line.ticker,
+            kind: IncomeKind::Distribution,
+            payment_date,
+            value,
+            date,
+        });
+    }
+
+    if distributions.is_empty() {
+        return Err(String::from("There were no distributions to import"));
+    }
+
+    Ok(distributions)
+}
+
+fn import(conn: &PgConnection, csv: Bytes) -> Result<usize, Error> {
+    parse(csv)
+        .map_err(Error::Parsing)
+        .and_then(|distributions| {
+            register_income_events(conn, &distributions).map_err(Error::Writing)
+        })
+}
+
+pub fn run(conn: &PgConnection, csv: Bytes) -> Result<usize, Error> {
+    let import_run_id = register_import_run(conn, "fii-distributions").map_err(Error::Writing)?;
+    let result = import(conn, csv);
+
+    finish_assetless_import_run(
+        conn,
+        import_run_id,
+        result.as_ref().map(|_| ()).map_err(|e| match e {
+            Error::Parsing(e) => format!("Parsing: {}", e),
+            Error::Writing(e) => format!("Writing: {}", e),
+        }),
+    );
+
+    result
+}
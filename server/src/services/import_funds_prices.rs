@@ -0,0 +1,106 @@
+use crate::models::{finish_import_run, register_fund_prices, register_import_run, PriceCounts};
+use bigdecimal::BigDecimal;
+use bytes::{buf::ext::BufExt, Bytes};
+use chrono::NaiveDate;
+use csv::ReaderBuilder;
+use diesel::PgConnection;
+use itertools::Itertools;
+use serde::Deserialize;
+use std::str::FromStr;
+
+pub enum Error {
+    Parsing(String),
+    Writing(diesel::result::Error),
+}
+
+pub enum WritingError {
+    Writing(diesel::result::Error),
+}
+
+type Results = Vec<(String, Result<PriceCounts, WritingError>)>;
+
+#[derive(Deserialize)]
+struct Line {
+    #[serde(rename = "CNPJ_FUNDO")]
+    cnpj: String,
+    #[serde(rename = "DT_COMPTC")]
+    date: String,
+    #[serde(rename = "VL_QUOTA")]
+    quota: String,
+}
+
+struct ParsedLine {
+    cnpj: String,
+    date: NaiveDate,
+    quota: BigDecimal,
+}
+
+// CVM files carry every fund in the country, so only the requested ones are kept
+fn parse(bytes: Bytes, cnpjs: &[String]) -> Result<Vec<ParsedLine>, Error> {
+    let lines = ReaderBuilder::new()
+        .delimiter(b';')
+        .from_reader(bytes.reader())
+        .into_deserialize();
+
+    let mut fund_prices = vec![];
+    for line in lines {
+        let line: Line = line.map_err(|e| Error::Parsing(format!("Some line is bad: {}", e)))?;
+
+        if !cnpjs.contains(&line.cnpj) {
+            continue;
+        }
+
+        let date = NaiveDate::parse_from_str(&line.date, "%Y-%m-%d")
+            .map_err(|e| Error::Parsing(format!("Some line date is bad: {}", e)))?;
+
+        let quota = BigDecimal::from_str(&line.quota)
+            .map_err(|e| Error::Parsing(format!("Some line quota is bad: {}", e)))?;
+
+        fund_prices.push(ParsedLine {
+            cnpj: line.cnpj,
+            quota,
+            date,
+        });
+    }
+
+    if fund_prices.is_empty() {
+        return Err(Error::Parsing(String::from(
+            "There were no quotas for the requested funds",
+        )));
+    }
+
+    Ok(fund_prices)
+}
+
+fn write(conn: &PgConnection, import_run_id: i32, lines: Vec<ParsedLine>) -> Results {
+    lines
+        .into_iter()
+        .sorted_by(|a, b| (&a.cnpj, a.date).cmp(&(&b.cnpj, b.date)))
+        .group_by(|fp| fp.cnpj.clone())
+        .into_iter()
+        .map(|(cnpj, fund_prices)| {
+            let prices = fund_prices.map(|fp| (fp.date, fp.quota)).collect();
+            let res = register_fund_prices(conn, &cnpj, import_run_id, prices)
+                .map_err(WritingError::Writing);
+            (cnpj, res)
+        })
+        .collect()
+}
+
+pub fn run(conn: &PgConnection, cnpjs: &[String], csv: Bytes) -> Result<Results, Error> {
+    let import_run_id = register_import_run(conn, "funds-prices").map_err(Error::Writing)?;
+    let result = parse(csv, cnpjs).map(|lines| write(conn, import_run_id, lines));
+
+    finish_import_run(
+        conn,
+        import_run_id,
+        result.as_ref().map(Vec::as_slice).map_err(|e| match e {
+            Error::Parsing(e) => format!("Parsing: {}", e),
+            Error::Writing(e) => format!("Writing: {}", e),
+        }),
+        String::clone,
+        |WritingError::Writing(e)| format!("Writing: {}", e),
+    );
+
+    result
+}
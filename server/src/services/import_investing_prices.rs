@@ -0,0 +1,127 @@
+use crate::models::{
+    crypto_currency, finish_import_run, register_crypto_prices, register_fii_prices,
+    register_import_run, register_stock_prices, Currency, PriceCounts,
+};
+use bigdecimal::BigDecimal;
+use bytes::{buf::ext::BufExt, Bytes};
+use chrono::NaiveDate;
+use csv::Reader;
+use diesel::PgConnection;
+use serde::Deserialize;
+use std::str::FromStr;
+
+pub enum Error {
+    Writing(diesel::result::Error),
+    Parsing(String),
+}
+
+pub enum Kind {
+    Stock,
+    Fii,
+    Crypto(Currency),
+}
+
+impl Kind {
+    // Only cryptos may be quoted in a currency other than reais, so they must say which
+    pub fn from_key(key: &str, currency: Option<&str>) -> Option<Kind> {
+        match (key, currency) {
+            ("stock", None) => Some(Kind::Stock),
+            ("fii", None) => Some(Kind::Fii),
+            ("crypto", Some(currency)) => Currency::from_key(currency).map(Kind::Crypto),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Line {
+    #[serde(rename = "Data")]
+    date: String,
+    #[serde(rename = "Último")]
+    price: String,
+}
+
+fn parse(bytes: Bytes) -> Result<Vec<(NaiveDate, BigDecimal)>, String> {
+    let lines = Reader::from_reader(bytes.reader()).into_deserialize();
+
+    let mut prices = vec![];
+    for line in lines {
+        let line: Line = line.map_err(|e| format!("Some line is bad: {}", e))?;
+
+        let date = NaiveDate::parse_from_str(&line.date, "%d.%m.%Y")
+            .map_err(|e| format!("Some line's date is bad: {}", e))?;
+
+        let price = BigDecimal::from_str(&line.price.replace(".", "").replace(",", "."))
+            .map_err(|e| format!("Some line's price is bad: {}", e))?;
+
+        prices.push((date, price));
+    }
+
+    if prices.is_empty() {
+        return Err(String::from("There were no prices to import"));
+    }
+
+    Ok(prices)
+}
+
+fn import(
+    conn: &PgConnection,
+    import_run_id: i32,
+    kind: Kind,
+    ticker: &str,
+    csv: Bytes,
+) -> Result<PriceCounts, Error> {
+    if let Kind::Crypto(currency) = kind {
+        match crypto_currency(conn, ticker).map_err(Error::Writing)? {
+            Some(stored_currency) if stored_currency != currency => {
+                return Err(Error::Parsing(format!(
+                    "{} is priced in {}",
+                    ticker,
+                    stored_currency.key()
+                )))
+            }
+            _ => {}
+        }
+    }
+
+    parse(csv).map_err(Error::Parsing).and_then(|prices| {
+        match kind {
+            Kind::Stock => register_stock_prices(conn, ticker, import_run_id, prices),
+            Kind::Fii => register_fii_prices(conn, ticker, import_run_id, prices),
+            Kind::Crypto(currency) => {
+                register_crypto_prices(conn, ticker, currency, import_run_id, prices)
+            }
+        }
+        .map_err(Error::Writing)
+    })
+}
+
+// A file that can't be parsed fails the whole run, while failing to write it fails the asset
+pub fn run(
+    conn: &PgConnection,
+    kind: Kind,
+    ticker: &str,
+    csv: Bytes,
+) -> Result<PriceCounts, Error> {
+    let import_run_id = register_import_run(conn, "investing-prices").map_err(Error::Writing)?;
+    let result = import(conn, import_run_id, kind, ticker, csv);
+
+    let results = match &result {
+        Err(Error::Parsing(e)) => Err(format!("Parsing: {}", e)),
+        Err(Error::Writing(e)) => Ok([(ticker, Err(e))]),
+        Ok(counts) => Ok([(ticker, Ok(*counts))]),
+    };
+
+    finish_import_run(
+        conn,
+        import_run_id,
+        results
+            .as_ref()
+            .map(|results| &results[..])
+            .map_err(String::clone),
+        |ticker| ticker.to_string(),
+        |e| format!("Writing: {}", e),
+    );
+
+    result
+}
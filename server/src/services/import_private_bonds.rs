@@ -0,0 +1,103 @@
+use crate::models::{
+    finish_assetless_import_run, register_import_run, register_private_bonds, BondIndex, BondKind,
+    PrivateBondIssue,
+};
+use bigdecimal::BigDecimal;
+use bytes::{buf::ext::BufExt, Bytes};
+use chrono::NaiveDate;
+use csv::Reader;
+use diesel::PgConnection;
+use serde::Deserialize;
+use std::str::FromStr;
+
+#[derive(Deserialize)]
+struct Line {
+    #[serde(rename = "Código")]
+    code: String,
+    #[serde(rename = "Emissor")]
+    issuer: String,
+    #[serde(rename = "Tipo")]
+    bond_kind: String,
+    #[serde(rename = "Indexador")]
+    index: String,
+    #[serde(rename = "Taxa")]
+    rate: String,
+    #[serde(rename = "Emissão")]
+    issue_date: String,
+    #[serde(rename = "Vencimento")]
+    maturity_date: String,
+    #[serde(rename = "PU")]
+    issue_price: String,
+}
+
+pub enum Error {
+    Writing(diesel::result::Error),
+    Parsing(String),
+}
+
+fn parse(bytes: Bytes) -> Result<Vec<PrivateBondIssue>, String> {
+    let lines = Reader::from_reader(bytes.reader()).into_deserialize();
+
+    let mut issues = vec![];
+
+    for line in lines {
+        let line: Line = line.map_err(|e| format!("Some line is bad: {}", e))?;
+
+        let bond_kind = BondKind::from_key(&line.bond_kind)
+            .ok_or_else(|| format!("Some line's kind is bad: {}", line.bond_kind))?;
+
+        let index = BondIndex::from_key(&line.index)
+            .ok_or_else(|| format!("Some line's index is bad: {}", line.index))?;
+
+        let rate = BigDecimal::from_str(&line.rate)
+            .map_err(|e| format!("Some line's rate is bad: {}", e))?;
+
+        let issue_date = NaiveDate::parse_from_str(&line.issue_date, "%d/%m/%Y")
+            .map_err(|e| format!("Some line's issue date is bad: {}", e))?;
+
+        let maturity_date = NaiveDate::parse_from_str(&line.maturity_date, "%d/%m/%Y")
+            .map_err(|e| format!("Some line's maturity date is bad: {}", e))?;
+
+        let issue_price = BigDecimal::from_str(&line.issue_price)
+            .map_err(|e| format!("Some line's issue price is bad: {}", e))?;
+
+        issues.push(PrivateBondIssue {
+            code: line.code,
+            issuer: line.issuer,
+            bond_kind,
+            index,
+            rate,
+            issue_date,
+            maturity_date,
+            issue_price,
+        });
+    }
+
+    if issues.is_empty() {
+        return Err(String::from("There were no bonds to import"));
+    }
+
+    Ok(issues)
+}
+
+fn import(conn: &PgConnection, csv: Bytes) -> Result<usize, Error> {
+    parse(csv)
+        .map_err(Error::Parsing)
+        .and_then(|issues| register_private_bonds(conn, &issues).map_err(Error::Writing))
+}
+
+pub fn run(conn: &PgConnection, csv: Bytes) -> Result<usize, Error> {
+    let import_run_id = register_import_run(conn, "private-bonds").map_err(Error::Writing)?;
+    let result = import(conn, csv);
+
+    finish_assetless_import_run(
+        conn,
+        import_run_id,
+        result.as_ref().map(|_| ()).map_err(|e| match e {
+            Error::Parsing(e) => format!("Parsing: {}", e),
+            Error::Writing(e) => format!("Writing: {}", e),
+        }),
+    );
+
+    result
+}
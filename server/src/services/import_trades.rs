@@ -1,5 +1,8 @@
-use crate::models::{register_trades, EtfTrade, TreasuryBondTrade};
-use bigdecimal::BigDecimal;
+use crate::models::{
+    finish_assetless_import_run, register_import_run, register_trades, EtfTrade, FiiTrade,
+    FundTrade, PortfolioTrades, PrivateBondTrade, StockTrade, TradeFees, TreasuryBondTrade,
+};
+use bigdecimal::{BigDecimal, Zero};
 use bytes::{buf::ext::BufExt, Bytes};
 use chrono::NaiveDate;
 use csv::Reader;
@@ -21,6 +24,10 @@ struct Line {
     asset_prop_1: String,
     #[serde(rename = "DadoAtivo2")]
     asset_prop_2: String,
+    #[serde(rename = "Corretagem", default)]
+    brokerage_fee: Option<String>,
+    #[serde(rename = "Emolumentos", default)]
+    emoluments: Option<String>,
 }
 
 pub enum Error {
@@ -28,11 +35,20 @@ pub enum Error {
     Parsing(String),
 }
 
-fn parse(bytes: Bytes) -> Result<(Vec<EtfTrade>, Vec<TreasuryBondTrade>), String> {
+// Fee columns are optional, and missing or empty ones mean no fee was paid
+fn parse_fee(fee: Option<&str>) -> Result<BigDecimal, String> {
+    match fee {
+        None | Some("") => Ok(BigDecimal::zero()),
+        Some(fee) => {
+            BigDecimal::from_str(fee).map_err(|e| format!("Some line's fee is bad: {}", e))
+        }
+    }
+}
+
+fn parse(bytes: Bytes) -> Result<PortfolioTrades, String> {
     let lines = Reader::from_reader(bytes.reader()).into_deserialize();
 
-    let mut etf_trades = vec![];
-    let mut treasury_bond_trades = vec![];
+    let mut trades = PortfolioTrades::default();
 
     for line in lines {
         let line: Line = line.map_err(|e| format!("Some line is bad: {}", e))?;
@@ -46,42 +62,99 @@ fn parse(bytes: Bytes) -> Result<(Vec<EtfTrade>, Vec<TreasuryBondTrade>), String
         let quantity = BigDecimal::from_str(&line.quantity)
             .map_err(|e| format!("Some line's quantity is bad: {}", e))?;
 
+        let fees = TradeFees {
+            brokerage: parse_fee(line.brokerage_fee.as_deref())?,
+            emoluments: parse_fee(line.emoluments.as_deref())?,
+        };
+
         match line.asset_kind.as_str() {
             "Tesouro" => {
                 let maturity = NaiveDate::parse_from_str(&line.asset_prop_2, "%d/%m/%Y")
                     .map_err(|e| format!("Some line's treasury maturity is bad: {}", e))?;
 
-                treasury_bond_trades.push(TreasuryBondTrade {
+                trades.treasury_bonds.push(TreasuryBondTrade {
                     key: line.asset_prop_1,
                     maturity,
                     quantity,
                     price,
                     date,
+                    fees,
                 });
             }
             "ETF" => {
-                etf_trades.push(EtfTrade {
+                trades.etfs.push(EtfTrade {
+                    ticker: line.asset_prop_1,
+                    quantity,
+                    price,
+                    date,
+                    fees,
+                });
+            }
+            "Ação" => {
+                trades.stocks.push(StockTrade {
+                    ticker: line.asset_prop_1,
+                    quantity,
+                    price,
+                    date,
+                    fees,
+                });
+            }
+            "FII" => {
+                trades.fiis.push(FiiTrade {
                     ticker: line.asset_prop_1,
                     quantity,
                     price,
                     date,
+                    fees,
+                });
+            }
+            "Fundo" => {
+                trades.funds.push(FundTrade {
+                    cnpj: line.asset_prop_1,
+                    quantity,
+                    price,
+                    date,
+                    fees,
+                });
+            }
+            "Privado" => {
+                trades.private_bonds.push(PrivateBondTrade {
+                    code: line.asset_prop_1,
+                    quantity,
+                    price,
+                    date,
+                    fees,
                 });
             }
             kind => return Err(format!("Some line's asset kind is bad: {}", kind)),
         };
     }
 
-    if etf_trades.len() + treasury_bond_trades.len() == 0 {
+    if trades.is_empty() {
         return Err(String::from("There were no trades to import"));
     }
 
-    Ok((etf_trades, treasury_bond_trades))
+    Ok(trades)
 }
 
-pub fn run(conn: &PgConnection, csv: Bytes) -> Result<i32, Error> {
+fn import(conn: &PgConnection, csv: Bytes) -> Result<i32, Error> {
     parse(csv)
         .map_err(Error::Parsing)
-        .and_then(|(etf_trades, treasury_bond_trades)| {
-            register_trades(conn, &etf_trades, &treasury_bond_trades).map_err(Error::Writing)
-        })
+        .and_then(|trades| register_trades(conn, &trades).map_err(Error::Writing))
+}
+
+pub fn run(conn: &PgConnection, csv: Bytes) -> Result<i32, Error> {
+    let import_run_id = register_import_run(conn, "trades").map_err(Error::Writing)?;
+    let result = import(conn, csv);
+
+    finish_assetless_import_run(
+        conn,
+        import_run_id,
+        result.as_ref().map(|_| ()).map_err(|e| match e {
+            Error::Parsing(e) => format!("Parsing: {}", e),
+            Error::Writing(e) => format!("Writing: {}", e),
+        }),
+    );
+
+    result
 }
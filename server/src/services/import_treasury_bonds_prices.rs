@@ -1,25 +1,20 @@
-use crate::models::register_treasury_bond_prices;
+use crate::models::{
+    finish_import_run, last_treasury_bond_price_date, register_import_run,
+    register_treasury_bond_coupons, register_treasury_bond_prices, register_treasury_bond_rates,
+    register_treasury_bond_redemption, PriceCounts,
+};
+use crate::services::price_sources::{Error, PriceSource, Registry};
 use actix_web::client::Client;
 use bigdecimal::BigDecimal;
 use bytes::{buf::ext::BufExt, Bytes};
 use chrono::NaiveDate;
 use csv::ReaderBuilder;
 use diesel::PgConnection;
+use futures::future::{FutureExt, LocalBoxFuture};
 use itertools::Itertools;
 use serde::Deserialize;
 use std::str::FromStr;
 
-pub enum ReadingError {
-    Network(actix_web::client::SendRequestError),
-    Status(actix_web::http::StatusCode),
-    Payload(actix_web::client::PayloadError),
-    Parsing(String),
-}
-
-pub enum WritingError {
-    Writing(diesel::result::Error),
-}
-
 #[derive(Deserialize)]
 struct Line {
     #[serde(rename = "Tipo Titulo")]
@@ -28,34 +23,92 @@ struct Line {
     maturity: String,
     #[serde(rename = "Data Base")]
     date: String,
+    #[serde(rename = "Taxa Compra Manha")]
+    buy_rate: String,
+    #[serde(rename = "Taxa Venda Manha")]
+    sell_rate: String,
     #[serde(rename = "PU Base Manha")]
     price: String,
 }
 
+// Tesouro Direto API listing of the bonds on sale, with the quotes of the moment
+#[derive(Deserialize)]
+struct ApiResponse {
+    response: ApiBody,
+}
+
+#[derive(Deserialize)]
+struct ApiBody {
+    #[serde(rename = "TrsrBondMkt")]
+    market: ApiMarket,
+    #[serde(rename = "TrsrBdTradgList")]
+    bonds: Vec<ApiBondEntry>,
+}
+
+#[derive(Deserialize)]
+struct ApiMarket {
+    #[serde(rename = "qtnDtTm")]
+    quotation_date: String,
+}
+
+#[derive(Deserialize)]
+struct ApiBondEntry {
+    #[serde(rename = "TrsrBd")]
+    bond: ApiBond,
+}
+
+#[derive(Deserialize)]
+struct ApiBond {
+    #[serde(rename = "nm")]
+    name: String,
+    #[serde(rename = "mtrtyDt")]
+    maturity: String,
+    #[serde(rename = "untrRedVal")]
+    price: f64,
+    #[serde(rename = "anulInvstmtRate")]
+    buy_rate: f64,
+    #[serde(rename = "anulRedRate")]
+    sell_rate: f64,
+}
+
 struct ParsedLine {
     key: &'static str,
     maturity: NaiveDate,
     date: NaiveDate,
     price: BigDecimal,
+    buy_rate: Option<BigDecimal>,
+    sell_rate: BigDecimal,
+}
+
+fn treasury_bond_key(kind: &str) -> Option<&'static str> {
+    match kind {
+        "Tesouro Selic" => Some("LFT"),
+        "Tesouro IPCA+" => Some("NTN-B Principal"),
+        "Tesouro IPCA+ com Juros Semestrais" => Some("NTN-B"),
+        "Tesouro Prefixado" => Some("LTN"),
+        "Tesouro Prefixado com Juros Semestrais" => Some("NTN-F"),
+        "Tesouro IGPM+ com Juros Semestrais" => Some("NTN-C"),
+        _ => None,
+    }
 }
 
-async fn fetch() -> Result<Bytes, ReadingError> {
-    let request = Client::default().get(URL);
-    let mut response = request.send().await.map_err(ReadingError::Network)?;
+async fn fetch(url: &str) -> Result<Bytes, Error> {
+    let request = Client::default().get(url);
+    let mut response = request.send().await.map_err(Error::Network)?;
 
     let status = response.status();
     if status != 200 {
-        return Err(ReadingError::Status(status));
+        return Err(Error::Status(status));
     }
 
     response
         .body()
         .limit(16777216)
         .await
-        .map_err(ReadingError::Payload)
+        .map_err(Error::Payload)
 }
 
-fn parse(bytes: Bytes) -> Result<Vec<ParsedLine>, ReadingError> {
+fn parse_csv(bytes: Bytes, start_date: NaiveDate) -> Result<Vec<ParsedLine>, Error> {
     let lines = ReaderBuilder::new()
         .delimiter(b';')
         .from_reader(bytes.reader())
@@ -63,41 +116,46 @@ fn parse(bytes: Bytes) -> Result<Vec<ParsedLine>, ReadingError> {
 
     let mut treasury_prices = vec![];
     for line in lines {
-        let line: Line =
-            line.map_err(|e| ReadingError::Parsing(format!("Some line is bad: {}", e)))?;
+        let line: Line = line.map_err(|e| Error::Parsing(format!("Some line is bad: {}", e)))?;
 
         if line.price == "" || line.price == "0,00" {
             continue;
         }
 
-        let key = match &line.kind[..] {
-            "Tesouro Selic" => "LFT",
-            "Tesouro IPCA+" => "NTN-B",
-            "Tesouro IPCA+ com Juros Semestrais" => "NTN-B Principal",
-            "Tesouro Prefixado" => "LTN",
-            "Tesouro Prefixado com Juros Semestrais" => "NTN-F",
-            "Tesouro IGPM+ com Juros Semestrais" => "NTN-C",
-            other_key => {
-                return Err(ReadingError::Parsing(format!(
-                    "Some line type is bad: {}",
-                    other_key
-                )))
-            }
-        };
+        let key = treasury_bond_key(&line.kind)
+            .ok_or_else(|| Error::Parsing(format!("Some line type is bad: {}", line.kind)))?;
 
         let price = BigDecimal::from_str(&line.price.replace(",", "."))
-            .map_err(|e| ReadingError::Parsing(format!("Some line price is bad: {}", e)))?;
+            .map_err(|e| Error::Parsing(format!("Some line price is bad: {}", e)))?;
+
+        let buy_rate = if line.buy_rate == "" || line.buy_rate == "0,00" {
+            None
+        } else {
+            Some(
+                BigDecimal::from_str(&line.buy_rate.replace(",", "."))
+                    .map_err(|e| Error::Parsing(format!("Some line buy rate is bad: {}", e)))?,
+            )
+        };
+
+        let sell_rate = BigDecimal::from_str(&line.sell_rate.replace(",", "."))
+            .map_err(|e| Error::Parsing(format!("Some line sell rate is bad: {}", e)))?;
 
         let date = NaiveDate::parse_from_str(&line.date, "%d/%m/%Y")
-            .map_err(|e| ReadingError::Parsing(format!("Some line date is bad: {}", e)))?;
+            .map_err(|e| Error::Parsing(format!("Some line date is bad: {}", e)))?;
 
         let maturity = NaiveDate::parse_from_str(&line.maturity, "%d/%m/%Y")
-            .map_err(|e| ReadingError::Parsing(format!("Some line maturity is bad: {}", e)))?;
+            .map_err(|e| Error::Parsing(format!("Some line maturity is bad: {}", e)))?;
+
+        if date < start_date {
+            continue;
+        }
 
         treasury_prices.push(ParsedLine {
             key,
             maturity,
             price,
+            buy_rate,
+            sell_rate,
             date,
         });
     }
@@ -105,31 +163,192 @@ fn parse(bytes: Bytes) -> Result<Vec<ParsedLine>, ReadingError> {
     Ok(treasury_prices)
 }
 
+fn parse_decimal(value: f64) -> Result<BigDecimal, Error> {
+    BigDecimal::from_str(&value.to_string())
+        .map_err(|e| Error::Parsing(format!("Some bond value is bad: {}", e)))
+}
+
+// Bonds are named after their kind and maturity year, like Tesouro Prefixado 2026,
+// and their buy rate is zeroed while they are not on sale
+fn parse_api(bytes: Bytes) -> Result<Vec<ParsedLine>, Error> {
+    let api_response: ApiResponse = serde_json::from_slice(&bytes)
+        .map_err(|e| Error::Parsing(format!("Body is bad: {}", e)))?;
+
+    let date = api_response
+        .response
+        .market
+        .quotation_date
+        .get(..10)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+        .ok_or_else(|| Error::Parsing("Quotation date is bad".into()))?;
+
+    let mut treasury_prices = vec![];
+    for entry in api_response.response.bonds {
+        let bond = entry.bond;
+
+        let key = bond
+            .name
+            .rsplitn(2, ' ')
+            .nth(1)
+            .and_then(treasury_bond_key)
+            .ok_or_else(|| Error::Parsing(format!("Some bond name is bad: {}", bond.name)))?;
+
+        let maturity = bond
+            .maturity
+            .get(..10)
+            .and_then(|maturity| NaiveDate::parse_from_str(maturity, "%Y-%m-%d").ok())
+            .ok_or_else(|| {
+                Error::Parsing(format!("Some bond maturity is bad: {}", bond.maturity))
+            })?;
+
+        if bond.price <= 0.0 {
+            continue;
+        }
+
+        let buy_rate = if bond.buy_rate > 0.0 {
+            Some(parse_decimal(bond.buy_rate)?)
+        } else {
+            None
+        };
+
+        treasury_prices.push(ParsedLine {
+            price: parse_decimal(bond.price)?,
+            sell_rate: parse_decimal(bond.sell_rate)?,
+            key,
+            maturity,
+            buy_rate,
+            date,
+        });
+    }
+
+    Ok(treasury_prices)
+}
+
+struct TesouroTransparente;
+
+impl PriceSource for TesouroTransparente {
+    type Asset = ();
+    type Prices = Vec<ParsedLine>;
+
+    fn name(&self) -> &'static str {
+        "tesouro-transparente"
+    }
+
+    // The whole history comes at once, so only the window asked for is kept
+    fn fetch<'a>(
+        &'a self,
+        _: &'a (),
+        start_date: NaiveDate,
+        _: NaiveDate,
+    ) -> LocalBoxFuture<'a, Result<Vec<ParsedLine>, Error>> {
+        async move {
+            fetch(CSV_URL)
+                .await
+                .and_then(|bytes| parse_csv(bytes, start_date))
+        }
+        .boxed_local()
+    }
+}
+
+struct TesouroDireto;
+
+impl PriceSource for TesouroDireto {
+    type Asset = ();
+    type Prices = Vec<ParsedLine>;
+
+    fn name(&self) -> &'static str {
+        "tesouro-direto"
+    }
+
+    // Only the latest quotes are available, whatever the window
+    fn fetch<'a>(
+        &'a self,
+        _: &'a (),
+        _: NaiveDate,
+        _: NaiveDate,
+    ) -> LocalBoxFuture<'a, Result<Vec<ParsedLine>, Error>> {
+        async move { fetch(API_URL).await.and_then(parse_api) }.boxed_local()
+    }
+}
+
+// The history from Tesouro Transparente is the reference, with the Tesouro Direto quotes
+// as the fallback to at least keep the latest prices coming
+fn registry() -> Registry<(), Vec<ParsedLine>> {
+    Registry::new(vec![Box::new(TesouroTransparente), Box::new(TesouroDireto)])
+}
+
 fn write(
     conn: &PgConnection,
+    import_run_id: i32,
     lines: Vec<ParsedLine>,
-) -> Vec<((&'static str, NaiveDate), Result<usize, WritingError>)> {
+    today: NaiveDate,
+) -> Vec<((&'static str, NaiveDate), Result<PriceCounts, Error>)> {
     lines
         .into_iter()
         .sorted_by_key(|tp| (tp.key, tp.maturity))
         .group_by(|tp| (tp.key, tp.maturity))
         .into_iter()
         .map(|((key, maturity), treasury_prices)| {
-            let prices = treasury_prices.map(|tp| (tp.date, tp.price)).collect();
-            let res = register_treasury_bond_prices(conn, key, maturity, prices)
-                .map_err(WritingError::Writing);
+            let mut prices = vec![];
+            let mut rates = vec![];
+
+            for tp in treasury_prices {
+                prices.push((tp.date, tp.price));
+                rates.push((tp.date, tp.buy_rate, tp.sell_rate));
+            }
+
+            let res = register_treasury_bond_prices(conn, key, maturity, import_run_id, prices)
+                .and_then(|count| {
+                    register_treasury_bond_rates(conn, key, maturity, rates).map(|_| count)
+                })
+                .and_then(|count| {
+                    register_treasury_bond_coupons(conn, key, maturity, today).map(|_| count)
+                })
+                .and_then(|count| {
+                    register_treasury_bond_redemption(conn, key, maturity, today).map(|_| count)
+                })
+                .map_err(Error::Writing);
             ((key, maturity), res)
         })
         .collect()
 }
 
+async fn import(
+    conn: &PgConnection,
+    import_run_id: i32,
+    today: NaiveDate,
+) -> Result<Vec<((&'static str, NaiveDate), Result<PriceCounts, Error>)>, Error> {
+    let start_date = last_treasury_bond_price_date(conn)
+        .map_err(Error::Writing)?
+        .unwrap_or_else(|| NaiveDate::from_ymd(2000, 1, 1));
+
+    let (_, lines) = registry()
+        .fetch("treasury_bonds", &(), start_date, today)
+        .await?;
+
+    Ok(write(conn, import_run_id, lines, today))
+}
+
 pub async fn run(
     conn: &PgConnection,
-) -> Result<Vec<((&'static str, NaiveDate), Result<usize, WritingError>)>, ReadingError> {
-    fetch()
-        .await
-        .and_then(|bytes| parse(bytes))
-        .map(|lines| write(conn, lines))
+    today: NaiveDate,
+) -> Result<Vec<((&'static str, NaiveDate), Result<PriceCounts, Error>)>, Error> {
+    let import_run_id =
+        register_import_run(conn, "treasury-bonds-prices").map_err(Error::Writing)?;
+    let result = import(conn, import_run_id, today).await;
+
+    finish_import_run(
+        conn,
+        import_run_id,
+        result.as_ref().map(Vec::as_slice).map_err(Error::message),
+        |(key, maturity)| format!("{}({})", key, maturity),
+        Error::message,
+    );
+
+    result
 }
 
-const URL: &'static str = "http://www.tesourotransparente.gov.br/ckan/dataset/df56aa42-484a-4a59-8184-7676580c81e3/resource/796d2059-14e9-44e3-80c9-2d9e30b405c1/download/PrecoTaxaTesouroDireto.csv";
+const CSV_URL: &str = "http://www.tesourotransparente.gov.br/ckan/dataset/df56aa42-484a-4a59-8184-7676580c81e3/resource/796d2059-14e9-44e3-80c9-2d9e30b405c1/download/PrecoTaxaTesouroDireto.csv";
+
+const API_URL: &str =
+    "https://www.tesourodireto.com.br/json/br/com/b3/tesourodireto/service/api/treasurybondsinfo.json";
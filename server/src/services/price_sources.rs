@@ -0,0 +1,113 @@
+use chrono::NaiveDate;
+use futures::future::LocalBoxFuture;
+
+pub enum Error {
+    Network(actix_web::client::SendRequestError),
+    Status(actix_web::http::StatusCode),
+    Payload(actix_web::client::PayloadError),
+    Parsing(String),
+    Writing(diesel::result::Error),
+}
+
+impl Error {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::Network(_) => "Network",
+            Error::Status(_) => "Status",
+            Error::Payload(_) => "Payload",
+            Error::Parsing(_) => "Parsing",
+            Error::Writing(_) => "Writing",
+        }
+    }
+
+    pub fn message(&self) -> String {
+        let detail = match self {
+            Error::Network(e) => e.to_string(),
+            Error::Status(e) => e.to_string(),
+            Error::Payload(e) => e.to_string(),
+            Error::Parsing(e) => e.clone(),
+            Error::Writing(e) => e.to_string(),
+        };
+
+        format!("{}: {}", self.kind(), detail)
+    }
+}
+
+// A provider of prices for some kind of asset, within a window of dates.
+// Sources that can't be asked for a window are free to return more than it.
+pub trait PriceSource {
+    type Asset;
+    type Prices;
+
+    fn name(&self) -> &'static str;
+
+    fn fetch<'a>(
+        &'a self,
+        asset: &'a Self::Asset,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> LocalBoxFuture<'a, Result<Self::Prices, Error>>;
+}
+
+pub type BoxedPriceSource<A, P> = Box<dyn PriceSource<Asset = A, Prices = P>>;
+
+// Every asset is tried against the sources in the order they were registered,
+// unless it has an order of its own. The next source is tried whenever one fails.
+pub struct Registry<A, P> {
+    sources: Vec<BoxedPriceSource<A, P>>,
+    orders: Vec<(String, Vec<&'static str>)>,
+}
+
+impl<A, P> Registry<A, P> {
+    pub fn new(sources: Vec<BoxedPriceSource<A, P>>) -> Registry<A, P> {
+        Registry {
+            sources,
+            orders: vec![],
+        }
+    }
+
+    pub fn order(mut self, asset_key: &str, source_names: &[&'static str]) -> Registry<A, P> {
+        self.orders
+            .push((asset_key.to_owned(), source_names.to_vec()));
+        self
+    }
+
+    fn sources(&self, asset_key: &str) -> Vec<&BoxedPriceSource<A, P>> {
+        let names = self
+            .orders
+            .iter()
+            .find(|(key, _)| key == asset_key)
+            .map_or_else(Vec::new, |(_, names)| names.clone());
+
+        let preferred = names
+            .iter()
+            .filter_map(|name| self.sources.iter().find(|source| source.name() == *name));
+
+        let remaining = self
+            .sources
+            .iter()
+            .filter(|source| !names.contains(&source.name()));
+
+        preferred.chain(remaining).collect()
+    }
+
+    // Returns the name of the source the prices came from, or the error of the last one tried
+    pub async fn fetch(
+        &self,
+        asset_key: &str,
+        asset: &A,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<(&'static str, P), Error> {
+        let mut last_error = Error::Parsing(format!("There are no sources for {}", asset_key));
+
+        for source in self.sources(asset_key) {
+            match source.fetch(asset, start_date, end_date).await {
+                Ok(prices) => return Ok((source.name(), prices)),
+                Err(e) => last_error = e,
+            }
+        }
+
+        Err(last_error)
+    }
+}
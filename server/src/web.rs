@@ -1,2 +1,4 @@
 mod cookies;
+mod counted_import;
+mod price_counts;
 pub mod routes;
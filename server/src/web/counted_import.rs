@@ -0,0 +1,56 @@
+use crate::services::{import_cash_movements, import_corporate_actions, import_income_events};
+use actix_web::{
+    web::{BytesMut, Payload},
+    HttpResponse,
+};
+use bytes::Bytes;
+use futures::StreamExt;
+
+pub enum Error {
+    Writing(diesel::result::Error),
+    Parsing(String),
+}
+
+// Every importer of portfolio events answers with how many of them it registered
+pub async fn post<E: Into<Error>>(
+    mut data: Payload,
+    import: impl FnOnce(Bytes) -> Result<usize, E>,
+) -> HttpResponse {
+    let mut csv = BytesMut::new();
+    while let Some(item) = data.next().await {
+        csv.extend_from_slice(&item.unwrap());
+    }
+
+    match import(csv.freeze()).map_err(Into::into) {
+        Err(Error::Parsing(e)) => HttpResponse::BadRequest().body(format!("ParsingError: {}", e)),
+        Err(Error::Writing(e)) => HttpResponse::BadRequest().body(format!("WritingError: {}", e)),
+        Ok(count) => HttpResponse::Ok().json(count),
+    }
+}
+
+impl From<import_cash_movements::Error> for Error {
+    fn from(e: import_cash_movements::Error) -> Error {
+        match e {
+            import_cash_movements::Error::Writing(e) => Error::Writing(e),
+            import_cash_movements::Error::Parsing(e) => Error::Parsing(e),
+        }
+    }
+}
+
+impl From<import_income_events::Error> for Error {
+    fn from(e: import_income_events::Error) -> Error {
+        match e {
+            import_income_events::Error::Writing(e) => Error::Writing(e),
+            import_income_events::Error::Parsing(e) => Error::Parsing(e),
+        }
+    }
+}
+
+impl From<import_corporate_actions::Error> for Error {
+    fn from(e: import_corporate_actions::Error) -> Error {
+        match e {
+            import_corporate_actions::Error::Writing(e) => Error::Writing(e),
+            import_corporate_actions::Error::Parsing(e) => Error::Parsing(e),
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use crate::models::PriceCounts;
+use serde::Serialize;
+
+// Every price importer answers with how its prices compared to the ones already stored
+#[derive(Serialize)]
+pub struct ResponsePriceCounts {
+    inserted: usize,
+    updated: usize,
+    unchanged: usize,
+}
+
+impl From<PriceCounts> for ResponsePriceCounts {
+    fn from(c: PriceCounts) -> ResponsePriceCounts {
+        ResponsePriceCounts {
+            inserted: c.inserted,
+            updated: c.updated,
+            unchanged: c.unchanged,
+        }
+    }
+}
@@ -1,9 +1,30 @@
 mod healthz;
+mod import_benchmark;
+mod import_cash_movements;
+mod import_corporate_actions;
+mod import_cotahist;
+mod import_crypto_trades;
 mod import_etfs_prices;
+mod import_exchange_rates;
+mod import_fii_distributions;
+mod import_funds_prices;
+mod import_income_events;
+mod import_investing_prices;
+mod import_private_bonds;
 mod import_trades;
 mod import_treasury_bonds_prices;
+mod imports;
+mod income;
 mod portfolio_amounts;
+mod portfolio_benchmark;
 mod portfolio_position;
+mod portfolio_returns;
+mod portfolio_twr;
+mod price_revisions;
+mod reports_annual_declaration;
+mod reports_darf;
+mod taxes;
+mod tracked_etfs;
 mod transactions;
 
 use actix_web::web::ServiceConfig;
@@ -12,8 +33,31 @@ pub fn config(cfg: &mut ServiceConfig) {
     cfg.service(healthz::healthz)
         .service(import_trades::post)
         .service(import_etfs_prices::post)
+        .service(import_cotahist::post)
         .service(import_treasury_bonds_prices::post)
+        .service(import_benchmark::post)
+        .service(import_investing_prices::post)
+        .service(import_fii_distributions::post)
+        .service(import_funds_prices::post)
+        .service(import_exchange_rates::post)
+        .service(import_crypto_trades::post)
+        .service(import_private_bonds::post)
+        .service(import_cash_movements::post)
+        .service(import_income_events::post)
+        .service(import_corporate_actions::post)
+        .service(tracked_etfs::get)
+        .service(tracked_etfs::post)
+        .service(tracked_etfs::delete)
+        .service(price_revisions::get)
+        .service(imports::get)
         .service(portfolio_position::get)
         .service(portfolio_amounts::get)
+        .service(portfolio_benchmark::get)
+        .service(portfolio_returns::get)
+        .service(portfolio_twr::get)
+        .service(taxes::get)
+        .service(income::get)
+        .service(reports_darf::get)
+        .service(reports_annual_declaration::get)
         .service(transactions::get);
 }
@@ -0,0 +1,35 @@
+use crate::database::Database;
+use crate::models::Benchmark;
+use crate::services::import_benchmarks::{run, Error};
+use actix_web::{
+    web::{BytesMut, Data, Payload, Query},
+    HttpResponse,
+};
+use futures::StreamExt;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Params {
+    benchmark: String,
+}
+
+#[actix_web::post("/import-benchmark")]
+pub async fn post(mut data: Payload, params: Query<Params>, db: Data<Database>) -> HttpResponse {
+    let conn = db.get().unwrap();
+
+    let benchmark = match Benchmark::from_key(&params.benchmark) {
+        Some(benchmark) => benchmark,
+        None => return HttpResponse::BadRequest().body("Unknown benchmark"),
+    };
+
+    let mut file = BytesMut::new();
+    while let Some(item) = data.next().await {
+        file.extend_from_slice(&item.unwrap());
+    }
+
+    match run(&conn, benchmark, file.freeze()) {
+        Err(Error::Parsing(e)) => HttpResponse::BadRequest().body(format!("ParsingError: {}", e)),
+        Err(Error::Writing(e)) => HttpResponse::BadRequest().body(format!("WritingError: {}", e)),
+        Ok(values_count) => HttpResponse::Ok().json(values_count),
+    }
+}
@@ -0,0 +1,14 @@
+use crate::database::Database;
+use crate::services::import_cash_movements::run;
+use crate::web::{cookies::PortfolioId, counted_import};
+use actix_web::{
+    web::{Data, Payload},
+    HttpResponse,
+};
+
+#[actix_web::post("/import-cash-movements")]
+pub async fn post(data: Payload, db: Data<Database>, portfolio_id: PortfolioId) -> HttpResponse {
+    let conn = db.get().unwrap();
+
+    counted_import::post(data, |csv| run(&conn, portfolio_id.0, csv)).await
+}
@@ -0,0 +1,42 @@
+use crate::database::Database;
+use crate::services::import_cotahist::run;
+use crate::services::price_sources::Error;
+use crate::web::price_counts::ResponsePriceCounts;
+use actix_web::{
+    web::{BytesMut, Data, Payload},
+    HttpResponse,
+};
+use futures::StreamExt;
+
+#[actix_web::post("/import-cotahist")]
+pub async fn post(mut data: Payload, db: Data<Database>) -> HttpResponse {
+    let conn = db.get().unwrap();
+
+    let mut txt = BytesMut::new();
+    while let Some(item) = data.next().await {
+        txt.extend_from_slice(&item.unwrap());
+    }
+
+    let results = match run(&conn, txt.freeze()) {
+        Err(Error::Parsing(e)) => {
+            return HttpResponse::BadRequest().body(format!("ParsingError: {}", e))
+        }
+        Err(Error::Writing(e)) => {
+            return HttpResponse::BadRequest().body(format!("WritingError: {}", e))
+        }
+        Err(_) => return HttpResponse::InternalServerError().body("something bad is not right"),
+        Ok(results) => results,
+    };
+
+    let results = results
+        .into_iter()
+        .map(|(ticker, result)| {
+            (
+                ticker,
+                result.map(ResponsePriceCounts::from).map_err(|e| e.kind()),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    HttpResponse::Ok().json(results)
+}
@@ -0,0 +1,28 @@
+use crate::database::Database;
+use crate::services::import_crypto_trades::{run, Error};
+use crate::web::cookies::PortfolioId;
+use actix_web::{
+    web::{BytesMut, Data, Payload},
+    HttpResponse,
+};
+use futures::StreamExt;
+
+#[actix_web::post("/import-crypto-trades")]
+pub async fn post(
+    mut data: Payload,
+    db: Data<Database>,
+    portfolio_id: PortfolioId,
+) -> HttpResponse {
+    let conn = db.get().unwrap();
+
+    let mut csv = BytesMut::new();
+    while let Some(item) = data.next().await {
+        csv.extend_from_slice(&item.unwrap());
+    }
+
+    match run(&conn, portfolio_id.0, csv.freeze()) {
+        Err(Error::Parsing(e)) => HttpResponse::BadRequest().body(format!("ParsingError: {}", e)),
+        Err(Error::Writing(e)) => HttpResponse::BadRequest().body(format!("WritingError: {}", e)),
+        Ok(trades_count) => HttpResponse::Ok().json(trades_count),
+    }
+}
@@ -1,25 +1,25 @@
 use crate::database::Database;
 use crate::services::import_etfs_prices;
+use crate::web::price_counts::ResponsePriceCounts;
 use actix_web::{web::Data, HttpResponse};
+use chrono::Utc;
 
 #[actix_web::post("/import-etfs-prices")]
 pub async fn post(db: Data<Database>) -> HttpResponse {
     let conn = db.get().unwrap();
+    let today = Utc::now().date().naive_utc();
 
-    let results = import_etfs_prices::run(&conn)
-        .await
+    let results = match import_etfs_prices::run(&conn, today).await {
+        Ok(results) => results,
+        Err(_) => return HttpResponse::InternalServerError().body("something bad is not right"),
+    };
+
+    let results = results
         .into_iter()
         .map(|(ticker, result)| {
             (
                 ticker,
-                match result {
-                    Ok(new_prices_count) => Ok(new_prices_count),
-                    Err(import_etfs_prices::Error::Network(_)) => Err("Network"),
-                    Err(import_etfs_prices::Error::Status(_)) => Err("Status"),
-                    Err(import_etfs_prices::Error::Payload(_)) => Err("Payload"),
-                    Err(import_etfs_prices::Error::Parsing(_)) => Err("Parsing"),
-                    Err(import_etfs_prices::Error::Writing(_)) => Err("Writing"),
-                },
+                result.map(ResponsePriceCounts::from).map_err(|e| e.kind()),
             )
         })
         .collect::<Vec<_>>();
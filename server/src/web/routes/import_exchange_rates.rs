@@ -0,0 +1,35 @@
+use crate::database::Database;
+use crate::models::Currency;
+use crate::services::import_exchange_rates::{run, Error};
+use actix_web::{
+    web::{BytesMut, Data, Payload, Query},
+    HttpResponse,
+};
+use futures::StreamExt;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Params {
+    currency: String,
+}
+
+#[actix_web::post("/import-exchange-rates")]
+pub async fn post(mut data: Payload, params: Query<Params>, db: Data<Database>) -> HttpResponse {
+    let conn = db.get().unwrap();
+
+    let currency = match Currency::from_key(&params.currency) {
+        Some(Currency::Brl) | None => return HttpResponse::BadRequest().body("Unknown currency"),
+        Some(currency) => currency,
+    };
+
+    let mut file = BytesMut::new();
+    while let Some(item) = data.next().await {
+        file.extend_from_slice(&item.unwrap());
+    }
+
+    match run(&conn, currency, file.freeze()) {
+        Err(Error::Parsing(e)) => HttpResponse::BadRequest().body(format!("ParsingError: {}", e)),
+        Err(Error::Writing(e)) => HttpResponse::BadRequest().body(format!("WritingError: {}", e)),
+        Ok(rates_count) => HttpResponse::Ok().json(rates_count),
+    }
+}
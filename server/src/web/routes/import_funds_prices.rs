@@ -0,0 +1,49 @@
+use crate::database::Database;
+use crate::services::import_funds_prices::{run, Error};
+use crate::web::price_counts::ResponsePriceCounts;
+use actix_web::{
+    web::{BytesMut, Data, Payload, Query},
+    HttpResponse,
+};
+use futures::StreamExt;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Params {
+    cnpjs: String,
+}
+
+#[actix_web::post("/import-funds-prices")]
+pub async fn post(mut data: Payload, params: Query<Params>, db: Data<Database>) -> HttpResponse {
+    let conn = db.get().unwrap();
+
+    let cnpjs: Vec<_> = params
+        .cnpjs
+        .split(',')
+        .map(|cnpj| cnpj.trim().to_owned())
+        .filter(|cnpj| !cnpj.is_empty())
+        .collect();
+
+    let mut csv = BytesMut::new();
+    while let Some(item) = data.next().await {
+        csv.extend_from_slice(&item.unwrap());
+    }
+
+    match run(&conn, &cnpjs, csv.freeze()) {
+        Err(Error::Parsing(e)) => HttpResponse::BadRequest().body(format!("ParsingError: {}", e)),
+        Err(Error::Writing(e)) => HttpResponse::BadRequest().body(format!("WritingError: {}", e)),
+        Ok(results) => {
+            let results: Vec<_> = results
+                .into_iter()
+                .map(|(cnpj, result)| {
+                    (
+                        cnpj,
+                        result.map(ResponsePriceCounts::from).map_err(|_| "Error"),
+                    )
+                })
+                .collect();
+
+            HttpResponse::Ok().json(results)
+        }
+    }
+}
@@ -0,0 +1,14 @@
+use crate::database::Database;
+use crate::services::import_income_events::run;
+use crate::web::counted_import;
+use actix_web::{
+    web::{Data, Payload},
+    HttpResponse,
+};
+
+#[actix_web::post("/import-income-events")]
+pub async fn post(data: Payload, db: Data<Database>) -> HttpResponse {
+    let conn = db.get().unwrap();
+
+    counted_import::post(data, |csv| run(&conn, csv)).await
+}
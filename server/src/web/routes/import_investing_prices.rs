@@ -0,0 +1,37 @@
+use crate::database::Database;
+use crate::services::import_investing_prices::{run, Error, Kind};
+use crate::web::price_counts::ResponsePriceCounts;
+use actix_web::{
+    web::{BytesMut, Data, Payload, Query},
+    HttpResponse,
+};
+use futures::StreamExt;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Params {
+    kind: String,
+    ticker: String,
+    currency: Option<String>,
+}
+
+#[actix_web::post("/import-investing-prices")]
+pub async fn post(mut data: Payload, params: Query<Params>, db: Data<Database>) -> HttpResponse {
+    let conn = db.get().unwrap();
+
+    let kind = match Kind::from_key(&params.kind, params.currency.as_deref()) {
+        Some(kind) => kind,
+        None => return HttpResponse::BadRequest().body("Unknown asset kind or currency"),
+    };
+
+    let mut csv = BytesMut::new();
+    while let Some(item) = data.next().await {
+        csv.extend_from_slice(&item.unwrap());
+    }
+
+    match run(&conn, kind, &params.ticker, csv.freeze()) {
+        Err(Error::Parsing(e)) => HttpResponse::BadRequest().body(format!("ParsingError: {}", e)),
+        Err(Error::Writing(e)) => HttpResponse::BadRequest().body(format!("WritingError: {}", e)),
+        Ok(counts) => HttpResponse::Ok().json(ResponsePriceCounts::from(counts)),
+    }
+}
@@ -0,0 +1,23 @@
+use crate::database::Database;
+use crate::services::import_private_bonds::{run, Error};
+use actix_web::{
+    web::{BytesMut, Data, Payload},
+    HttpResponse,
+};
+use futures::StreamExt;
+
+#[actix_web::post("/import-private-bonds")]
+pub async fn post(mut data: Payload, db: Data<Database>) -> HttpResponse {
+    let conn = db.get().unwrap();
+
+    let mut csv = BytesMut::new();
+    while let Some(item) = data.next().await {
+        csv.extend_from_slice(&item.unwrap());
+    }
+
+    match run(&conn, csv.freeze()) {
+        Err(Error::Parsing(e)) => HttpResponse::BadRequest().body(format!("ParsingError: {}", e)),
+        Err(Error::Writing(e)) => HttpResponse::BadRequest().body(format!("WritingError: {}", e)),
+        Ok(bonds_count) => HttpResponse::Ok().json(bonds_count),
+    }
+}
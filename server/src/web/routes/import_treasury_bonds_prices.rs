@@ -1,26 +1,37 @@
 use crate::database::Database;
-use crate::services::import_treasury_bonds_prices::{run, ReadingError};
+use crate::services::import_treasury_bonds_prices::run;
+use crate::services::price_sources::Error;
+use crate::web::price_counts::ResponsePriceCounts;
 use actix_web::{web::Data, HttpResponse};
+use chrono::Utc;
 
 #[actix_web::post("/import-treasury-bonds-prices")]
 pub async fn post(db: Data<Database>) -> HttpResponse {
     let conn = db.get().unwrap();
+    let today = Utc::now().date().naive_utc();
 
-    match run(&conn).await {
-        Err(ReadingError::Network(e)) => HttpResponse::Ok().body(format!("Network Error: {:?}", e)),
-        Err(ReadingError::Parsing(e)) => HttpResponse::Ok().body(format!("Bad Parsing: {:?}", e)),
-        Err(ReadingError::Status(e)) => HttpResponse::Ok().body(format!("Bad Status: {:?}", e)),
-        Err(ReadingError::Payload(_)) => HttpResponse::Ok().body("Bad Payload"),
+    match run(&conn, today).await {
+        Err(Error::Network(e)) => HttpResponse::Ok().body(format!("Network Error: {:?}", e)),
+        Err(Error::Parsing(e)) => HttpResponse::Ok().body(format!("Bad Parsing: {:?}", e)),
+        Err(Error::Status(e)) => HttpResponse::Ok().body(format!("Bad Status: {:?}", e)),
+        Err(Error::Payload(_)) => HttpResponse::Ok().body("Bad Payload"),
+        Err(Error::Writing(e)) => HttpResponse::Ok().body(format!("Bad Writing: {:?}", e)),
         Ok(results) => {
-            let bs: Vec<_> = results
+            let results: Vec<_> = results
                 .into_iter()
-                .filter(|(_, result)| result.is_err() || result.as_ref().ok().unwrap() > &0)
+                .filter(|(_, result)| match result {
+                    Ok(counts) => counts.inserted > 0 || counts.updated > 0,
+                    Err(_) => true,
+                })
                 .map(|((key, maturity), result)| {
-                    format!("{}({}) : {:?}", key, maturity, result.map_err(|_| "Error"))
+                    (
+                        format!("{}({})", key, maturity),
+                        result.map(ResponsePriceCounts::from).map_err(|_| "Error"),
+                    )
                 })
                 .collect();
 
-            HttpResponse::Ok().json(bs)
+            HttpResponse::Ok().json(results)
         }
     }
 }
@@ -0,0 +1,91 @@
+use crate::{
+    database::Database,
+    models::{ImportOutcome, ImportRun, ImportRunAsset},
+    services::get_import_runs,
+};
+use actix_web::{web, HttpResponse};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+pub struct Params {
+    source: Option<String>,
+    limit: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct ResponseImportRunAsset {
+    asset: String,
+    inserted: usize,
+    updated: usize,
+    unchanged: usize,
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ResponseImportRun {
+    id: i32,
+    source: String,
+    started_at: NaiveDateTime,
+    finished_at: Option<NaiveDateTime>,
+    outcome: Option<&'static str>,
+    error: Option<String>,
+    assets: Vec<ResponseImportRunAsset>,
+}
+
+impl From<ImportRunAsset> for ResponseImportRunAsset {
+    fn from(a: ImportRunAsset) -> ResponseImportRunAsset {
+        match a.result {
+            Ok(counts) => ResponseImportRunAsset {
+                asset: a.asset,
+                inserted: counts.inserted,
+                updated: counts.updated,
+                unchanged: counts.unchanged,
+                error: None,
+            },
+            Err(error) => ResponseImportRunAsset {
+                asset: a.asset,
+                inserted: 0,
+                updated: 0,
+                unchanged: 0,
+                error: Some(error),
+            },
+        }
+    }
+}
+
+impl From<ImportRun> for ResponseImportRun {
+    fn from(ir: ImportRun) -> ResponseImportRun {
+        ResponseImportRun {
+            id: ir.id,
+            source: ir.source,
+            started_at: ir.started_at,
+            finished_at: ir.finished_at,
+            outcome: ir.outcome.map(ImportOutcome::key),
+            error: ir.error,
+            assets: ir.assets.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[actix_web::get("/imports")]
+pub async fn get(db: web::Data<Database>, params: web::Query<Params>) -> HttpResponse {
+    let conn = db.get().unwrap();
+
+    // Postgres refuses negative limits, and asking for too many runs would load all their assets
+    let limit = match params.limit {
+        None => 50,
+        Some(limit) if limit < 0 => {
+            return HttpResponse::BadRequest().body("The limit can't be negative")
+        }
+        Some(limit) => limit.min(500),
+    };
+
+    let result = get_import_runs::run(&conn, params.source.as_deref(), limit);
+
+    match result {
+        Err(_) => HttpResponse::InternalServerError().body("something bad is not right"),
+        Ok(runs) => HttpResponse::Ok()
+            .json::<Vec<ResponseImportRun>>(runs.into_iter().map(Into::into).collect()),
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{
+    database::Database,
+    services::get_monthly_income::{self, MonthlyIncome},
+    web::cookies::PortfolioId,
+};
+use actix_web::{web, HttpResponse};
+use bigdecimal::ToPrimitive;
+use chrono::{NaiveDate, Utc};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct ResponseMonthlyIncome {
+    month: NaiveDate,
+    dividends: f32,
+    jcp: f32,
+    distributions: f32,
+    coupons: f32,
+    withheld: f32,
+    total: f32,
+}
+
+impl From<MonthlyIncome> for ResponseMonthlyIncome {
+    fn from(mi: MonthlyIncome) -> ResponseMonthlyIncome {
+        ResponseMonthlyIncome {
+            month: mi.month,
+            dividends: mi.dividends.with_scale(2).to_f32().unwrap(),
+            jcp: mi.jcp.with_scale(2).to_f32().unwrap(),
+            distributions: mi.distributions.with_scale(2).to_f32().unwrap(),
+            coupons: mi.coupons.with_scale(2).to_f32().unwrap(),
+            withheld: mi.withheld.with_scale(2).to_f32().unwrap(),
+            total: mi.total.with_scale(2).to_f32().unwrap(),
+        }
+    }
+}
+
+#[actix_web::get("/income")]
+pub async fn get(db: web::Data<Database>, portfolio_id: PortfolioId) -> HttpResponse {
+    let conn = db.get().unwrap();
+    let today = Utc::now().date().naive_utc();
+
+    let result = get_monthly_income::run(&conn, portfolio_id.0, today);
+
+    match result {
+        Err(_) => HttpResponse::InternalServerError().body("something bad is not right"),
+        Ok(incomes) => HttpResponse::Ok()
+            .json::<Vec<ResponseMonthlyIncome>>(incomes.into_iter().map(Into::into).collect()),
+    }
+}
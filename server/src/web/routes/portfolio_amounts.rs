@@ -9,7 +9,7 @@ use chrono::{NaiveDate, Utc};
 use serde::Serialize;
 
 #[derive(Serialize)]
-struct ResponsePortfolioAmount((NaiveDate, f32, f32));
+struct ResponsePortfolioAmount((NaiveDate, f32, f32, f32, f32, f32));
 
 impl From<PortfolioAmount> for ResponsePortfolioAmount {
     fn from(pa: PortfolioAmount) -> ResponsePortfolioAmount {
@@ -17,6 +17,9 @@ impl From<PortfolioAmount> for ResponsePortfolioAmount {
             pa.date,
             pa.invested.with_scale(2).to_f32().unwrap(),
             pa.gross_total.with_scale(2).to_f32().unwrap(),
+            pa.income.with_scale(2).to_f32().unwrap(),
+            pa.cash.with_scale(2).to_f32().unwrap(),
+            pa.contributions.with_scale(2).to_f32().unwrap(),
         ))
     }
 }
@@ -0,0 +1,47 @@
+use crate::{
+    database::Database,
+    models::Benchmark,
+    services::get_portfolio_benchmark::{self, BenchmarkComparison},
+    web::cookies::PortfolioId,
+};
+use actix_web::{web, HttpResponse};
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+pub struct Params {
+    benchmark: String,
+}
+
+#[derive(Serialize)]
+struct ResponseBenchmarkComparison((NaiveDate, f64, Option<f64>));
+
+impl From<BenchmarkComparison> for ResponseBenchmarkComparison {
+    fn from(bc: BenchmarkComparison) -> ResponseBenchmarkComparison {
+        ResponseBenchmarkComparison((bc.date, bc.portfolio, bc.benchmark))
+    }
+}
+
+#[actix_web::get("/portfolio-benchmark")]
+pub async fn get(
+    db: web::Data<Database>,
+    params: web::Query<Params>,
+    portfolio_id: PortfolioId,
+) -> HttpResponse {
+    let conn = db.get().unwrap();
+    let today = Utc::now().date().naive_utc();
+
+    let benchmark = match Benchmark::from_key(&params.benchmark) {
+        Some(benchmark) => benchmark,
+        None => return HttpResponse::BadRequest().body("Unknown benchmark"),
+    };
+
+    let result = get_portfolio_benchmark::run(&conn, portfolio_id.0, benchmark, today);
+
+    match result {
+        Err(_) => HttpResponse::InternalServerError().body("something bad is not right"),
+        Ok(comparisons) => HttpResponse::Ok().json::<Vec<ResponseBenchmarkComparison>>(
+            comparisons.into_iter().map(Into::into).collect(),
+        ),
+    }
+}
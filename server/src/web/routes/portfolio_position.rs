@@ -1,6 +1,6 @@
 use crate::{
     database::Database,
-    models::{position, AssetPosition, Assetable, PortfolioPosition},
+    models::{position, AssetPosition, Assetable, PortfolioPosition, Sell},
     web::cookies::PortfolioId,
 };
 use actix_web::{web, HttpResponse};
@@ -13,6 +13,20 @@ use serde::Serialize;
 enum ResponseAssetable {
     TreasuryBond(NaiveDate),
     Etf(String),
+    Stock(String),
+    Fii(String),
+    Fund(String),
+    Crypto(String),
+    PrivateBond(String),
+}
+
+#[derive(Serialize)]
+struct ResponseSell {
+    date: NaiveDate,
+    quantity: f64,
+    price: f32,
+    average_price: f32,
+    profit: f32,
 }
 
 #[derive(Serialize)]
@@ -20,13 +34,24 @@ struct ResponseAssetPosition {
     assetable: ResponseAssetable,
     amount: f32,
     price: f32,
-    quantity: f32,
+    quantity: f64,
+    average_price: f32,
+    cost: f32,
+    realized_profit: f32,
+    unrealized_profit: f32,
+    income: f32,
+    sells: Vec<ResponseSell>,
+    contracted_rate: Option<f32>,
+    market_rate: Option<f32>,
 }
 
 #[derive(Serialize)]
 struct ResponsePortfolioPosition {
     assets: Vec<ResponseAssetPosition>,
+    settled: Vec<ResponseAssetPosition>,
     amount: f32,
+    income: f32,
+    cash: f32,
 }
 
 impl From<Assetable> for ResponseAssetable {
@@ -34,6 +59,25 @@ impl From<Assetable> for ResponseAssetable {
         match a {
             Assetable::TreasuryBond(t) => ResponseAssetable::TreasuryBond(t.maturity_date),
             Assetable::Etf(etf) => ResponseAssetable::Etf(etf.ticker),
+            Assetable::Stock(stock) => ResponseAssetable::Stock(stock.ticker),
+            Assetable::Fii(fii) => ResponseAssetable::Fii(fii.ticker),
+            Assetable::Fund(fund) => ResponseAssetable::Fund(fund.cnpj),
+            Assetable::Crypto(crypto) => ResponseAssetable::Crypto(crypto.symbol),
+            Assetable::PrivateBond(private_bond) => {
+                ResponseAssetable::PrivateBond(private_bond.code)
+            }
+        }
+    }
+}
+
+impl From<Sell> for ResponseSell {
+    fn from(s: Sell) -> ResponseSell {
+        ResponseSell {
+            date: s.date,
+            quantity: s.quantity.with_scale(8).to_f64().unwrap(),
+            price: s.price.with_scale(2).to_f32().unwrap(),
+            average_price: s.average_price.with_scale(2).to_f32().unwrap(),
+            profit: s.profit.with_scale(2).to_f32().unwrap(),
         }
     }
 }
@@ -44,7 +88,19 @@ impl From<AssetPosition> for ResponseAssetPosition {
             assetable: ap.assetable.into(),
             amount: ap.amount.with_scale(2).to_f32().unwrap(),
             price: ap.price.with_scale(2).to_f32().unwrap(),
-            quantity: ap.quantity.with_scale(2).to_f32().unwrap(),
+            quantity: ap.quantity.with_scale(8).to_f64().unwrap(),
+            average_price: ap.average_price.with_scale(2).to_f32().unwrap(),
+            cost: ap.cost.with_scale(2).to_f32().unwrap(),
+            realized_profit: ap.realized_profit.with_scale(2).to_f32().unwrap(),
+            unrealized_profit: ap.unrealized_profit.with_scale(2).to_f32().unwrap(),
+            income: ap.income.with_scale(2).to_f32().unwrap(),
+            sells: ap.sells.into_iter().map(Into::into).collect(),
+            contracted_rate: ap
+                .contracted_rate
+                .map(|rate| rate.with_scale(2).to_f32().unwrap()),
+            market_rate: ap
+                .market_rate
+                .map(|rate| rate.with_scale(2).to_f32().unwrap()),
         }
     }
 }
@@ -53,7 +109,10 @@ impl From<PortfolioPosition> for ResponsePortfolioPosition {
     fn from(pp: PortfolioPosition) -> ResponsePortfolioPosition {
         ResponsePortfolioPosition {
             amount: pp.amount.with_scale(2).to_f32().unwrap(),
+            income: pp.income.with_scale(2).to_f32().unwrap(),
+            cash: pp.cash.with_scale(2).to_f32().unwrap(),
             assets: pp.assets.into_iter().map(Into::into).collect(),
+            settled: pp.settled.into_iter().map(Into::into).collect(),
         }
     }
 }
@@ -0,0 +1,80 @@
+use crate::{
+    database::Database,
+    models::Assetable,
+    services::get_portfolio_returns::{self, AssetReturn, PortfolioReturns},
+    web::cookies::PortfolioId,
+};
+use actix_web::{web, HttpResponse};
+use chrono::{NaiveDate, Utc};
+use serde::Serialize;
+
+#[derive(Serialize)]
+#[serde(tag = "type", content = "data")]
+enum ResponseAssetable {
+    TreasuryBond(NaiveDate),
+    Etf(String),
+    Stock(String),
+    Fii(String),
+    Fund(String),
+    Crypto(String),
+    PrivateBond(String),
+}
+
+#[derive(Serialize)]
+struct ResponseAssetReturn {
+    assetable: ResponseAssetable,
+    xirr: Option<f64>,
+}
+
+#[derive(Serialize)]
+struct ResponsePortfolioReturns {
+    assets: Vec<ResponseAssetReturn>,
+    xirr: Option<f64>,
+}
+
+impl From<Assetable> for ResponseAssetable {
+    fn from(a: Assetable) -> ResponseAssetable {
+        match a {
+            Assetable::TreasuryBond(t) => ResponseAssetable::TreasuryBond(t.maturity_date),
+            Assetable::Etf(etf) => ResponseAssetable::Etf(etf.ticker),
+            Assetable::Stock(stock) => ResponseAssetable::Stock(stock.ticker),
+            Assetable::Fii(fii) => ResponseAssetable::Fii(fii.ticker),
+            Assetable::Fund(fund) => ResponseAssetable::Fund(fund.cnpj),
+            Assetable::Crypto(crypto) => ResponseAssetable::Crypto(crypto.symbol),
+            Assetable::PrivateBond(private_bond) => {
+                ResponseAssetable::PrivateBond(private_bond.code)
+            }
+        }
+    }
+}
+
+impl From<AssetReturn> for ResponseAssetReturn {
+    fn from(ar: AssetReturn) -> ResponseAssetReturn {
+        ResponseAssetReturn {
+            assetable: ar.assetable.into(),
+            xirr: ar.xirr,
+        }
+    }
+}
+
+impl From<PortfolioReturns> for ResponsePortfolioReturns {
+    fn from(pr: PortfolioReturns) -> ResponsePortfolioReturns {
+        ResponsePortfolioReturns {
+            assets: pr.assets.into_iter().map(Into::into).collect(),
+            xirr: pr.xirr,
+        }
+    }
+}
+
+#[actix_web::get("/portfolio-returns")]
+pub async fn get(db: web::Data<Database>, portfolio_id: PortfolioId) -> HttpResponse {
+    let conn = db.get().unwrap();
+    let today = Utc::now().date().naive_utc();
+
+    let result = get_portfolio_returns::run(&conn, portfolio_id.0, today);
+
+    match result {
+        Err(_) => HttpResponse::InternalServerError().body("something bad is not right"),
+        Ok(returns) => HttpResponse::Ok().json::<ResponsePortfolioReturns>(returns.into()),
+    }
+}
@@ -0,0 +1,64 @@
+use crate::{
+    database::Database,
+    services::get_portfolio_twr::{self, ChainedReturn, PortfolioTwr, TwrIndex},
+    web::cookies::PortfolioId,
+};
+use actix_web::{web, HttpResponse};
+use chrono::{NaiveDate, Utc};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct ResponseTwrIndex((NaiveDate, f64));
+
+#[derive(Serialize)]
+struct ResponseChainedReturn {
+    start: NaiveDate,
+    end: NaiveDate,
+    value: f64,
+}
+
+#[derive(Serialize)]
+struct ResponsePortfolioTwr {
+    indexes: Vec<ResponseTwrIndex>,
+    monthly: Vec<ResponseChainedReturn>,
+    yearly: Vec<ResponseChainedReturn>,
+}
+
+impl From<TwrIndex> for ResponseTwrIndex {
+    fn from(ti: TwrIndex) -> ResponseTwrIndex {
+        ResponseTwrIndex((ti.date, ti.index))
+    }
+}
+
+impl From<ChainedReturn> for ResponseChainedReturn {
+    fn from(cr: ChainedReturn) -> ResponseChainedReturn {
+        ResponseChainedReturn {
+            start: cr.start,
+            end: cr.end,
+            value: cr.value,
+        }
+    }
+}
+
+impl From<PortfolioTwr> for ResponsePortfolioTwr {
+    fn from(pt: PortfolioTwr) -> ResponsePortfolioTwr {
+        ResponsePortfolioTwr {
+            indexes: pt.indexes.into_iter().map(Into::into).collect(),
+            monthly: pt.monthly.into_iter().map(Into::into).collect(),
+            yearly: pt.yearly.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[actix_web::get("/portfolio-twr")]
+pub async fn get(db: web::Data<Database>, portfolio_id: PortfolioId) -> HttpResponse {
+    let conn = db.get().unwrap();
+    let today = Utc::now().date().naive_utc();
+
+    let result = get_portfolio_twr::run(&conn, portfolio_id.0, today);
+
+    match result {
+        Err(_) => HttpResponse::InternalServerError().body("something bad is not right"),
+        Ok(twr) => HttpResponse::Ok().json::<ResponsePortfolioTwr>(twr.into()),
+    }
+}
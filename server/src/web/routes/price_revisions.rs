@@ -0,0 +1,64 @@
+use crate::{
+    database::Database,
+    models::PriceRevision,
+    services::get_price_revisions::{self, Asset},
+};
+use actix_web::{web, HttpResponse};
+use bigdecimal::ToPrimitive;
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+pub struct Params {
+    asset_id: Option<i32>,
+    ticker: Option<String>,
+    key: Option<String>,
+    maturity: Option<NaiveDate>,
+    date: Option<NaiveDate>,
+}
+
+#[derive(Serialize)]
+struct ResponsePriceRevision {
+    date: NaiveDate,
+    price: f64,
+    import_run_id: Option<i32>,
+    source: Option<String>,
+    created_at: NaiveDateTime,
+}
+
+impl From<PriceRevision> for ResponsePriceRevision {
+    fn from(pr: PriceRevision) -> ResponsePriceRevision {
+        ResponsePriceRevision {
+            date: pr.date,
+            price: pr.price.with_scale(8).to_f64().unwrap(),
+            import_run_id: pr.import_run_id,
+            source: pr.source,
+            created_at: pr.created_at,
+        }
+    }
+}
+
+#[actix_web::get("/price-revisions")]
+pub async fn get(db: web::Data<Database>, params: web::Query<Params>) -> HttpResponse {
+    let conn = db.get().unwrap();
+    let params = params.into_inner();
+
+    let asset = match (params.asset_id, params.ticker, params.key, params.maturity) {
+        (Some(asset_id), None, None, None) => Asset::Id(asset_id),
+        (None, Some(ticker), None, None) => Asset::Ticker(ticker),
+        (None, None, Some(key), Some(maturity)) => Asset::TreasuryBond(key, maturity),
+        _ => {
+            return HttpResponse::BadRequest()
+                .body("Either an asset id, a ticker or a treasury bond key and maturity is needed")
+        }
+    };
+
+    let result = get_price_revisions::run(&conn, &asset, params.date);
+
+    match result {
+        Err(diesel::result::Error::NotFound) => HttpResponse::NotFound().body("Unknown asset"),
+        Err(_) => HttpResponse::InternalServerError().body("something bad is not right"),
+        Ok(revisions) => HttpResponse::Ok()
+            .json::<Vec<ResponsePriceRevision>>(revisions.into_iter().map(Into::into).collect()),
+    }
+}
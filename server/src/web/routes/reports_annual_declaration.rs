@@ -0,0 +1,120 @@
+use crate::{
+    database::Database,
+    models::Assetable,
+    services::get_annual_declaration::{self, DeclarationItem},
+    web::cookies::PortfolioId,
+};
+use actix_web::{web, HttpResponse};
+use bigdecimal::ToPrimitive;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+pub struct Params {
+    year: i32,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", content = "data")]
+enum ResponseAssetable {
+    TreasuryBond {
+        key: String,
+        maturity_date: NaiveDate,
+    },
+    Etf {
+        ticker: String,
+    },
+    Stock {
+        ticker: String,
+    },
+    Fii {
+        ticker: String,
+    },
+    Fund {
+        cnpj: String,
+    },
+    Crypto {
+        symbol: String,
+    },
+    PrivateBond {
+        code: String,
+        issuer: String,
+    },
+}
+
+#[derive(Serialize)]
+struct ResponseDeclarationItem {
+    assetable: ResponseAssetable,
+    group: &'static str,
+    code: &'static str,
+    quantity: f64,
+    cost: f64,
+    previous_quantity: f64,
+    previous_cost: f64,
+}
+
+impl From<Assetable> for ResponseAssetable {
+    fn from(a: Assetable) -> ResponseAssetable {
+        match a {
+            Assetable::TreasuryBond(t) => ResponseAssetable::TreasuryBond {
+                key: t.key,
+                maturity_date: t.maturity_date,
+            },
+            Assetable::Etf(etf) => ResponseAssetable::Etf { ticker: etf.ticker },
+            Assetable::Stock(stock) => ResponseAssetable::Stock {
+                ticker: stock.ticker,
+            },
+            Assetable::Fii(fii) => ResponseAssetable::Fii { ticker: fii.ticker },
+            Assetable::Fund(fund) => ResponseAssetable::Fund { cnpj: fund.cnpj },
+            Assetable::Crypto(crypto) => ResponseAssetable::Crypto {
+                symbol: crypto.symbol,
+            },
+            Assetable::PrivateBond(private_bond) => ResponseAssetable::PrivateBond {
+                code: private_bond.code,
+                issuer: private_bond.issuer,
+            },
+        }
+    }
+}
+
+impl From<DeclarationItem> for ResponseDeclarationItem {
+    fn from(di: DeclarationItem) -> ResponseDeclarationItem {
+        ResponseDeclarationItem {
+            assetable: di.assetable.into(),
+            group: di.group,
+            code: di.code,
+            quantity: di.quantity.with_scale(8).to_f64().unwrap(),
+            cost: di.cost.with_scale(2).to_f64().unwrap(),
+            previous_quantity: di.previous_quantity.with_scale(8).to_f64().unwrap(),
+            previous_cost: di.previous_cost.with_scale(2).to_f64().unwrap(),
+        }
+    }
+}
+
+#[actix_web::get("/reports/annual-declaration")]
+pub async fn get(
+    db: web::Data<Database>,
+    params: web::Query<Params>,
+    portfolio_id: PortfolioId,
+) -> HttpResponse {
+    let conn = db.get().unwrap();
+
+    let year_end = NaiveDate::from_ymd_opt(params.year, 12, 31);
+    let previous_year_end = params
+        .year
+        .checked_sub(1)
+        .and_then(|previous_year| NaiveDate::from_ymd_opt(previous_year, 12, 31));
+
+    let (year_end, previous_year_end) = match (year_end, previous_year_end) {
+        (Some(year_end), Some(previous_year_end)) => (year_end, previous_year_end),
+        _ => return HttpResponse::BadRequest().body("Invalid year"),
+    };
+
+    let result = get_annual_declaration::run(&conn, portfolio_id.0, year_end, previous_year_end);
+
+    match result {
+        Err(_) => HttpResponse::InternalServerError().body("something bad is not right"),
+        Ok(items) => HttpResponse::Ok()
+            .json::<Vec<ResponseDeclarationItem>>(items.into_iter().map(Into::into).collect()),
+    }
+}
@@ -0,0 +1,40 @@
+use crate::{database::Database, services::export_darf, web::cookies::PortfolioId};
+use actix_web::{web, HttpResponse};
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Params {
+    month: String,
+    format: String,
+}
+
+#[actix_web::get("/reports/darf")]
+pub async fn get(
+    db: web::Data<Database>,
+    params: web::Query<Params>,
+    portfolio_id: PortfolioId,
+) -> HttpResponse {
+    let conn = db.get().unwrap();
+
+    let month = match NaiveDate::parse_from_str(&format!("{}-01", params.month), "%Y-%m-%d") {
+        Ok(month) => month,
+        Err(_) => return HttpResponse::BadRequest().body("Month should be like 2020-08"),
+    };
+
+    let darf = match export_darf::run(&conn, portfolio_id.0, month) {
+        Ok(Some(darf)) => darf,
+        Ok(None) => return HttpResponse::BadRequest().body("Invalid month"),
+        Err(_) => return HttpResponse::InternalServerError().body("something bad is not right"),
+    };
+
+    match &params.format[..] {
+        "csv" => HttpResponse::Ok()
+            .content_type("text/csv; charset=utf-8")
+            .body(darf.to_csv()),
+        "html" => HttpResponse::Ok()
+            .content_type("text/html; charset=utf-8")
+            .body(darf.to_html()),
+        _ => HttpResponse::BadRequest().body("Format should be csv or html"),
+    }
+}
@@ -0,0 +1,74 @@
+use crate::{
+    database::Database,
+    models::{monthly_taxes, MonthlyTax},
+    web::cookies::PortfolioId,
+};
+use actix_web::{web, HttpResponse};
+use bigdecimal::ToPrimitive;
+use chrono::{NaiveDate, Utc};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct ResponseMonthlyTax {
+    month: NaiveDate,
+    swing_trade_sales: f32,
+    stock_sales: f32,
+    stock_exempt_profit: f32,
+    swing_trade_result: f32,
+    swing_trade_losses_used: f32,
+    swing_trade_base: f32,
+    swing_trade_tax: f32,
+    carried_losses: f32,
+    fii_result: f32,
+    fii_losses_used: f32,
+    fii_base: f32,
+    fii_tax: f32,
+    fii_carried_losses: f32,
+    treasury_bond_iof: f32,
+    treasury_bond_ir: f32,
+    fund_iof: f32,
+    fund_ir: f32,
+    private_bond_iof: f32,
+    private_bond_ir: f32,
+}
+
+impl From<MonthlyTax> for ResponseMonthlyTax {
+    fn from(mt: MonthlyTax) -> ResponseMonthlyTax {
+        ResponseMonthlyTax {
+            month: mt.month,
+            swing_trade_sales: mt.swing_trade_sales.with_scale(2).to_f32().unwrap(),
+            stock_sales: mt.stock_sales.with_scale(2).to_f32().unwrap(),
+            stock_exempt_profit: mt.stock_exempt_profit.with_scale(2).to_f32().unwrap(),
+            swing_trade_result: mt.swing_trade_result.with_scale(2).to_f32().unwrap(),
+            swing_trade_losses_used: mt.swing_trade_losses_used.with_scale(2).to_f32().unwrap(),
+            swing_trade_base: mt.swing_trade_base.with_scale(2).to_f32().unwrap(),
+            swing_trade_tax: mt.swing_trade_tax.with_scale(2).to_f32().unwrap(),
+            carried_losses: mt.carried_losses.with_scale(2).to_f32().unwrap(),
+            fii_result: mt.fii_result.with_scale(2).to_f32().unwrap(),
+            fii_losses_used: mt.fii_losses_used.with_scale(2).to_f32().unwrap(),
+            fii_base: mt.fii_base.with_scale(2).to_f32().unwrap(),
+            fii_tax: mt.fii_tax.with_scale(2).to_f32().unwrap(),
+            fii_carried_losses: mt.fii_carried_losses.with_scale(2).to_f32().unwrap(),
+            treasury_bond_iof: mt.treasury_bond_iof.with_scale(2).to_f32().unwrap(),
+            treasury_bond_ir: mt.treasury_bond_ir.with_scale(2).to_f32().unwrap(),
+            fund_iof: mt.fund_iof.with_scale(2).to_f32().unwrap(),
+            fund_ir: mt.fund_ir.with_scale(2).to_f32().unwrap(),
+            private_bond_iof: mt.private_bond_iof.with_scale(2).to_f32().unwrap(),
+            private_bond_ir: mt.private_bond_ir.with_scale(2).to_f32().unwrap(),
+        }
+    }
+}
+
+#[actix_web::get("/taxes")]
+pub async fn get(db: web::Data<Database>, portfolio_id: PortfolioId) -> HttpResponse {
+    let conn = db.get().unwrap();
+    let today = Utc::now().date().naive_utc();
+
+    let result = monthly_taxes(&conn, portfolio_id.0, today);
+
+    match result {
+        Err(_) => HttpResponse::InternalServerError().body("something bad is not right"),
+        Ok(taxes) => HttpResponse::Ok()
+            .json::<Vec<ResponseMonthlyTax>>(taxes.into_iter().map(Into::into).collect()),
+    }
+}
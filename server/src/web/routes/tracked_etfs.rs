@@ -0,0 +1,75 @@
+use crate::{
+    database::Database,
+    models::{register_tracked_etf, remove_tracked_etf, tracked_etfs, TrackedEtf},
+};
+use actix_web::{web, HttpResponse};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+pub struct PostParams {
+    ticker: String,
+    #[serde(default)]
+    curr_id: String,
+    #[serde(default)]
+    sml_id: String,
+}
+
+#[derive(Deserialize)]
+pub struct DeleteParams {
+    ticker: String,
+}
+
+#[derive(Serialize)]
+struct ResponseTrackedEtf {
+    ticker: String,
+    curr_id: String,
+    sml_id: String,
+}
+
+impl From<TrackedEtf> for ResponseTrackedEtf {
+    fn from(te: TrackedEtf) -> ResponseTrackedEtf {
+        ResponseTrackedEtf {
+            ticker: te.ticker,
+            curr_id: te.curr_id,
+            sml_id: te.sml_id,
+        }
+    }
+}
+
+#[actix_web::get("/tracked-etfs")]
+pub async fn get(db: web::Data<Database>) -> HttpResponse {
+    let conn = db.get().unwrap();
+
+    match tracked_etfs(&conn) {
+        Err(_) => HttpResponse::InternalServerError().body("something bad is not right"),
+        Ok(tracked_etfs) => HttpResponse::Ok()
+            .json::<Vec<ResponseTrackedEtf>>(tracked_etfs.into_iter().map(Into::into).collect()),
+    }
+}
+
+#[actix_web::post("/tracked-etfs")]
+pub async fn post(db: web::Data<Database>, params: web::Query<PostParams>) -> HttpResponse {
+    let conn = db.get().unwrap();
+    let params = params.into_inner();
+
+    let tracked_etf = TrackedEtf {
+        ticker: params.ticker.trim().to_uppercase(),
+        curr_id: params.curr_id,
+        sml_id: params.sml_id,
+    };
+
+    match register_tracked_etf(&conn, &tracked_etf) {
+        Err(e) => HttpResponse::BadRequest().body(format!("WritingError: {}", e)),
+        Ok(count) => HttpResponse::Ok().json(count),
+    }
+}
+
+#[actix_web::delete("/tracked-etfs")]
+pub async fn delete(db: web::Data<Database>, params: web::Query<DeleteParams>) -> HttpResponse {
+    let conn = db.get().unwrap();
+
+    match remove_tracked_etf(&conn, &params.ticker.trim().to_uppercase()) {
+        Err(e) => HttpResponse::BadRequest().body(format!("WritingError: {}", e)),
+        Ok(count) => HttpResponse::Ok().json(count),
+    }
+}
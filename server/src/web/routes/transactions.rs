@@ -14,6 +14,11 @@ use serde::Serialize;
 enum ResponseAssetable {
     TreasuryBond(NaiveDate),
     Etf(String),
+    Stock(String),
+    Fii(String),
+    Fund(String),
+    Crypto(String),
+    PrivateBond(String),
 }
 
 #[derive(Serialize)]
@@ -23,6 +28,7 @@ struct ResponseTransaction {
     price: f64,
     quantity: f64,
     amount: f64,
+    fees: f64,
 }
 
 impl From<Assetable> for ResponseAssetable {
@@ -30,6 +36,13 @@ impl From<Assetable> for ResponseAssetable {
         match a {
             Assetable::TreasuryBond(t) => ResponseAssetable::TreasuryBond(t.maturity_date),
             Assetable::Etf(etf) => ResponseAssetable::Etf(etf.ticker),
+            Assetable::Stock(stock) => ResponseAssetable::Stock(stock.ticker),
+            Assetable::Fii(fii) => ResponseAssetable::Fii(fii.ticker),
+            Assetable::Fund(fund) => ResponseAssetable::Fund(fund.cnpj),
+            Assetable::Crypto(crypto) => ResponseAssetable::Crypto(crypto.symbol),
+            Assetable::PrivateBond(private_bond) => {
+                ResponseAssetable::PrivateBond(private_bond.code)
+            }
         }
     }
 }
@@ -40,6 +53,7 @@ impl From<Transaction> for ResponseTransaction {
             price: t.price.with_scale(8).to_f64().unwrap(),
             quantity: t.quantity.with_scale(8).to_f64().unwrap(),
             amount: t.amount.with_scale(2).to_f64().unwrap(),
+            fees: t.fees.with_scale(2).to_f64().unwrap(),
             assetable: t.assetable.into(),
             date: t.date,
         }